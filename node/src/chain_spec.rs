@@ -1,5 +1,5 @@
 use cumulus_primitives_core::ParaId;
-use Proof_runtime::{AccountId, AuraId, Signature, EXISTENTIAL_DEPOSIT};
+use Proof_runtime::{AccountId, AuraId, KittyTreasuryAccount, Signature, EXISTENTIAL_DEPOSIT};
 use sc_chain_spec::{ChainSpecExtension, ChainSpecGroup};
 use sc_service::ChainType;
 use serde::{Deserialize, Serialize};
@@ -192,7 +192,14 @@ fn testnet_genesis(
 				.to_vec(),
 		},
 		balances: Proof_runtime::BalancesConfig {
-			balances: endowed_accounts.iter().cloned().map(|k| (k, 1 << 60)).collect(),
+			balances: endowed_accounts
+				.iter()
+				.cloned()
+				.map(|k| (k, 1 << 60))
+				// The kitty marketplace fees are paid into this account, so it must exist
+				// before the first sale, otherwise fees below the existential deposit fail.
+				.chain(std::iter::once((KittyTreasuryAccount::get(), EXISTENTIAL_DEPOSIT)))
+				.collect(),
 		},
 		sudo: Proof_runtime::SudoConfig { key: Some(root_key)},
 		parachain_info: Proof_runtime::ParachainInfoConfig { parachain_id: id },
@@ -221,5 +228,6 @@ fn testnet_genesis(
 		polkadot_xcm: Proof_runtime::PolkadotXcmConfig {
 			safe_xcm_version: Some(SAFE_XCM_VERSION),
 		},
+		proof_module: Proof_runtime::ProofModuleConfig { claims: vec![] },
 	}
 }
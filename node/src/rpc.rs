@@ -7,7 +7,7 @@
 
 use std::sync::Arc;
 
-use Proof_runtime::{opaque::Block, AccountId, Balance, Index as Nonce};
+use Proof_runtime::{opaque::Block, AccountId, Balance, BlockNumber, Index as Nonce};
 
 use sc_client_api::AuxStore;
 pub use sc_rpc::{DenyUnsafe, SubscriptionTaskExecutor};
@@ -41,9 +41,13 @@ where
 		+ 'static,
 	C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
 	C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Nonce>,
+	C::Api: pallet_proof_rpc::ProofRuntimeApi<Block, AccountId, BlockNumber>,
+	C::Api: pallet_kitties_rpc::KittiesRuntimeApi<Block, AccountId, Balance, BlockNumber>,
 	C::Api: BlockBuilder<Block>,
 	P: TransactionPool + Sync + Send + 'static,
 {
+	use pallet_kitties_rpc::{Kitties, KittiesApi};
+	use pallet_proof_rpc::{Proof, ProofApi};
 	use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApi};
 	use substrate_frame_rpc_system::{FullSystem, SystemApi};
 
@@ -51,7 +55,9 @@ where
 	let FullDeps { client, pool, deny_unsafe } = deps;
 
 	io.extend_with(SystemApi::to_delegate(FullSystem::new(client.clone(), pool, deny_unsafe)));
-	io.extend_with(TransactionPaymentApi::to_delegate(TransactionPayment::new(client)));
+	io.extend_with(TransactionPaymentApi::to_delegate(TransactionPayment::new(client.clone())));
+	io.extend_with(ProofApi::to_delegate(Proof::new(client.clone())));
+	io.extend_with(KittiesApi::to_delegate(Kitties::new(client)));
 
 	io
 }
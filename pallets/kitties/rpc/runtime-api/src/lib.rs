@@ -0,0 +1,34 @@
+//! kitty模块(pallet-kitties)的Runtime API定义，供RPC查询kitty交易市场使用
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_std::vec::Vec;
+
+pub use pallet_kitties::{
+	FamilyTreeNode, GetKittyMarketResult, Kitty, KittyIndex, KittyTraits, RarityTier, TraitValue, KittyInfo, KittyInfoById, MarketCursor, MarketPage,
+	MarketQuery, MarketSortOrder, OwnedKittiesPage, OwnedKitty,
+};
+
+sp_api::decl_runtime_apis! {
+	pub trait KittiesApi<AccountId, Balance, BlockNumber> where
+		AccountId: Codec,
+		Balance: Codec,
+		BlockNumber: Codec,
+	{
+		/// 查询交易市场中所有挂单的kitty，包括kitty的所有者、价格和dna
+		fn kitty_market() -> GetKittyMarketResult<AccountId, Balance>;
+		/// 分页查询交易市场，支持按所有者、价格区间过滤以及排序
+		fn kitty_market_page(query: MarketQuery<AccountId, Balance>) -> MarketPage<AccountId, Balance>;
+		/// 分页查询账户拥有的kitty，cursor为上一页最后一个kittyId
+		fn kitties_of(
+			owner: AccountId,
+			cursor: Option<KittyIndex>,
+			limit: u32,
+		) -> OwnedKittiesPage<Balance>;
+		/// 查询kitty的家谱，从kitty开始按代向上追溯最多depth代祖先
+		fn family_tree(kitty_index: KittyIndex, depth: u32) -> Vec<FamilyTreeNode<BlockNumber>>;
+		/// 解码kitty的dna，得到外观属性以及稀有度得分
+		fn kitty_traits(kitty_index: KittyIndex) -> Option<KittyTraits>;
+	}
+}
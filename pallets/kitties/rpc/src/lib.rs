@@ -0,0 +1,164 @@
+//! kitty模块(pallet-kitties)的RPC接口，前端通过一次调用即可获取整个交易市场
+
+use std::sync::Arc;
+
+use codec::Codec;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+pub use pallet_kitties_rpc_runtime_api::{
+	FamilyTreeNode, GetKittyMarketResult, KittyTraits, KittiesApi as KittiesRuntimeApi, KittyIndex, KittyInfoById, MarketPage,
+	MarketQuery, OwnedKittiesPage,
+};
+
+#[rpc]
+pub trait KittiesApi<BlockHash, AccountId, Balance, BlockNumber> {
+	/// 查询交易市场中所有挂单的kitty，包括kitty的所有者、价格和dna
+	#[rpc(name = "kitties_marketInfo")]
+	fn kitty_market(
+		&self,
+		at: Option<BlockHash>,
+	) -> Result<GetKittyMarketResult<AccountId, Balance>>;
+
+	/// 分页查询交易市场，支持按所有者、价格区间过滤以及排序
+	#[rpc(name = "kitties_marketPage")]
+	fn kitty_market_page(
+		&self,
+		query: MarketQuery<AccountId, Balance>,
+		at: Option<BlockHash>,
+	) -> Result<MarketPage<AccountId, Balance>>;
+
+	/// 分页查询账户拥有的kitty，cursor为上一页最后一个kittyId
+	#[rpc(name = "kitties_kittiesOf")]
+	fn kitties_of(
+		&self,
+		owner: AccountId,
+		cursor: Option<KittyIndex>,
+		limit: u32,
+		at: Option<BlockHash>,
+	) -> Result<OwnedKittiesPage<Balance>>;
+
+	/// 查询kitty的家谱，从kitty开始按代向上追溯最多depth代祖先
+	#[rpc(name = "kitties_familyTree")]
+	fn family_tree(
+		&self,
+		kitty_index: KittyIndex,
+		depth: u32,
+		at: Option<BlockHash>,
+	) -> Result<Vec<FamilyTreeNode<BlockNumber>>>;
+
+	/// 解码kitty的dna，得到外观属性以及稀有度得分
+	#[rpc(name = "kitties_kittyTraits")]
+	fn kitty_traits(
+		&self,
+		kitty_index: KittyIndex,
+		at: Option<BlockHash>,
+	) -> Result<Option<KittyTraits>>;
+}
+
+/// 实现kitty查询RPC的结构体
+pub struct Kitties<C, B> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> Kitties<C, B> {
+	/// 通过client创建一个新的kitty查询RPC实例
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+/// RPC的错误类型
+pub enum Error {
+	/// 调用runtime api失败
+	RuntimeError,
+}
+
+impl From<Error> for i64 {
+	fn from(e: Error) -> i64 {
+		match e {
+			Error::RuntimeError => 1,
+		}
+	}
+}
+
+impl<C, Block, AccountId, Balance, BlockNumber>
+	KittiesApi<<Block as BlockT>::Hash, AccountId, Balance, BlockNumber> for Kitties<C, Block>
+where
+	Block: BlockT,
+	C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: KittiesRuntimeApi<Block, AccountId, Balance, BlockNumber>,
+	AccountId: Codec,
+	Balance: Codec,
+	BlockNumber: Codec,
+{
+	fn kitty_market(
+		&self,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<GetKittyMarketResult<AccountId, Balance>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.kitty_market(&at).map_err(runtime_error_into_rpc_err)
+	}
+
+	fn kitty_market_page(
+		&self,
+		query: MarketQuery<AccountId, Balance>,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<MarketPage<AccountId, Balance>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.kitty_market_page(&at, query).map_err(runtime_error_into_rpc_err)
+	}
+
+	fn kitties_of(
+		&self,
+		owner: AccountId,
+		cursor: Option<KittyIndex>,
+		limit: u32,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<OwnedKittiesPage<Balance>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.kitties_of(&at, owner, cursor, limit).map_err(runtime_error_into_rpc_err)
+	}
+
+	fn family_tree(
+		&self,
+		kitty_index: KittyIndex,
+		depth: u32,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<FamilyTreeNode<BlockNumber>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.family_tree(&at, kitty_index, depth).map_err(runtime_error_into_rpc_err)
+	}
+
+	fn kitty_traits(
+		&self,
+		kitty_index: KittyIndex,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<KittyTraits>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.kitty_traits(&at, kitty_index).map_err(runtime_error_into_rpc_err)
+	}
+}
+
+/// 将runtime api的错误转换为RPC错误
+fn runtime_error_into_rpc_err(err: impl std::fmt::Debug) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(Error::RuntimeError.into()),
+		message: "Runtime error".into(),
+		data: Some(format!("{:?}", err).into()),
+	}
+}
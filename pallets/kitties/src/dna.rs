@@ -0,0 +1,130 @@
+//! kitty的dna解码以及遗传规则
+//!
+//! dna的前8个字节是4个外观属性的基因，每个属性占2个字节，第一个字节为显性基因，第二个字节为隐性基因，
+//! 外观由显性基因决定。后8个字节为隐藏基因，繁殖时按位混合。
+//! 前端和链上逻辑都使用这里的规则解码dna，不依赖std。
+
+use codec::{Decode, Encode};
+use scale_info::TypeInfo;
+use sp_runtime::RuntimeDebug;
+
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+/// dna的长度
+pub const DNA_LEN: usize = 16;
+/// 每个稀有度等级中的外观种类数量
+pub const VARIANTS_PER_TIER: u8 = 4;
+
+/// 外观属性在dna中的位置，每个属性为(显性基因, 隐性基因)两个字节
+pub const BODY: usize = 0;
+pub const EYES: usize = 2;
+pub const COLOUR: usize = 4;
+pub const PATTERN: usize = 6;
+/// 外观属性基因的总长度，之后的字节为隐藏基因
+pub const ATTRIBUTES_LEN: usize = 8;
+
+/// 稀有度等级，按基因字节的取值范围划分，约为60%、25%、12%、3%
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum RarityTier {
+	Common,
+	Uncommon,
+	Rare,
+	Legendary,
+}
+
+impl RarityTier {
+	/// 基因字节对应的稀有度等级
+	pub fn of(gene: u8) -> Self {
+		match gene {
+			0..=152 => RarityTier::Common,
+			153..=216 => RarityTier::Uncommon,
+			217..=247 => RarityTier::Rare,
+			_ => RarityTier::Legendary,
+		}
+	}
+
+	/// 稀有度等级的得分
+	pub fn score(&self) -> u32 {
+		match self {
+			RarityTier::Common => 1,
+			RarityTier::Uncommon => 2,
+			RarityTier::Rare => 5,
+			RarityTier::Legendary => 10,
+		}
+	}
+}
+
+/// 一个外观属性，包括稀有度等级以及等级中的外观种类
+#[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct TraitValue {
+	pub tier: RarityTier,
+	pub variant: u8,
+}
+
+impl TraitValue {
+	/// 从显性基因解码外观属性
+	pub fn from_gene(gene: u8) -> Self {
+		TraitValue { tier: RarityTier::of(gene), variant: gene % VARIANTS_PER_TIER }
+	}
+}
+
+/// dna解码出的kitty外观，以及由各个属性的稀有度得出的稀有度得分
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct KittyTraits {
+	pub body: TraitValue,
+	pub eyes: TraitValue,
+	pub colour: TraitValue,
+	pub pattern: TraitValue,
+	pub rarity_score: u32,
+}
+
+/// 解码dna的外观属性
+pub fn decode(dna: &[u8; DNA_LEN]) -> KittyTraits {
+	let body = TraitValue::from_gene(dna[BODY]);
+	let eyes = TraitValue::from_gene(dna[EYES]);
+	let colour = TraitValue::from_gene(dna[COLOUR]);
+	let pattern = TraitValue::from_gene(dna[PATTERN]);
+	let rarity_score = [body, eyes, colour, pattern].iter().map(|value| value.tier.score()).sum();
+	KittyTraits { body, eyes, colour, pattern, rarity_score }
+}
+
+/// 根据父母的dna和随机数得到child的dna
+///
+/// 每个外观属性由父母各随机提供一个基因(显性或者隐性)，稀有度较低的基因为显性，
+/// 稀有度相同时随机决定。隐藏基因按随机数的位从父母中选择。
+pub fn inherit(
+	dna_1: &[u8; DNA_LEN],
+	dna_2: &[u8; DNA_LEN],
+	random: &[u8; DNA_LEN],
+) -> [u8; DNA_LEN] {
+	let mut child = [0u8; DNA_LEN];
+
+	for attribute in [BODY, EYES, COLOUR, PATTERN] {
+		let selector = random[attribute];
+		let gene_1 = dna_1[attribute + (selector & 1) as usize];
+		let gene_2 = dna_2[attribute + ((selector >> 1) & 1) as usize];
+
+		let first_dominant = match RarityTier::of(gene_1).cmp(&RarityTier::of(gene_2)) {
+			sp_std::cmp::Ordering::Less => true,
+			sp_std::cmp::Ordering::Greater => false,
+			sp_std::cmp::Ordering::Equal => (selector >> 2) & 1 == 1,
+		};
+		let (dominant, recessive) =
+			if first_dominant { (gene_1, gene_2) } else { (gene_2, gene_1) };
+		child[attribute] = dominant;
+		child[attribute + 1] = recessive;
+	}
+
+	for i in ATTRIBUTES_LEN..DNA_LEN {
+		// 当随机数的位为1的时候就使用dna_1的值，为0的时候就使用dna_2的值
+		child[i] = (random[i] & dna_1[i]) | (!random[i] & dna_2[i]);
+	}
+
+	child
+}
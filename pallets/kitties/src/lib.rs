@@ -1,6 +1,9 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+pub mod dna;
+pub mod migrations;
 mod types;
+pub mod weights;
 
 #[cfg(test)]
 mod mock;
@@ -8,6 +11,7 @@ mod mock;
 mod tests;
 
 pub use pallet::*;
+pub use weights::WeightInfo;
 
 #[frame_support::pallet]
 pub mod pallet {
@@ -16,16 +20,47 @@ pub mod pallet {
 		dispatch::{fmt::Debug, Codec, DispatchResult},
 		pallet_prelude::*,
 		sp_io::hashing::blake2_128,
+		storage::{with_transaction, StoragePrefixedMap, TransactionOutcome},
+		transactional,
 	};
 	// 引入随机数以及代币、可质押代币ReservableCurrency（用于后续创建kitty时的质押）
 	use frame_support::traits::{
-		Currency, ExistenceRequirement::KeepAlive, Randomness, ReservableCurrency,
+		tokens::nonfungibles::{Inspect, Mutate, Transfer},
+		BalanceStatus, Currency, ExistenceRequirement::KeepAlive, Randomness, ReservableCurrency,
+		StorageVersion,
 	};
-	use frame_system::pallet_prelude::*;
-	use sp_runtime::traits::{AtLeast32BitUnsigned, Bounded, One, Zero, SaturatedConversion};
-	use sp_std::{prelude::*, vec::Vec};
+	use cumulus_pallet_xcm::{ensure_sibling_para, Origin as CumulusOrigin};
+	use cumulus_primitives_core::ParaId;
+	use frame_system::{pallet_prelude::*, Config as SystemConfig};
+	use sp_runtime::{
+		traits::{AtLeast32BitUnsigned, Bounded, One, Zero, SaturatedConversion, Saturating},
+		Permill, TokenError,
+	};
+	use sp_std::{prelude::*, vec, vec::Vec};
+	use xcm::latest::prelude::*;
 
-	pub use crate::types::{GetKittyMarketResult, KittyInfoById, KittyInfo, MarketKittyqueryError, Kitty};
+	pub use crate::dna::{KittyTraits, RarityTier, TraitValue};
+	pub use crate::types::{
+		Auction, FamilyTreeNode, GetKittyMarketResult, Kitty, KittyGenealogy, KittyIndex, KittyInfo,
+		KittyInfoById, Listing, MarketCursor, PendingMint, MarketKittyqueryError, MarketPage, MarketQuery, MarketSortOrder, OwnedKittiesPage, OwnedKitty,
+	};
+	use crate::WeightInfo;
+
+	// 分页查询时每页最多返回的记录数
+	pub const MAX_PAGE_SIZE: u32 = 100;
+	// 交易市场的有序索引为每种排序方式各保存一份
+	const MARKET_SORT_ORDERS: [MarketSortOrder; 4] = [
+		MarketSortOrder::IndexAsc,
+		MarketSortOrder::IndexDesc,
+		MarketSortOrder::PriceAsc,
+		MarketSortOrder::PriceDesc,
+	];
+	// 除了当前区块，每个区块最多追赶多少个因为没有随机数而被推迟揭示的区块
+	const MAX_REVEAL_BLOCKS: u32 = 4;
+	// 查询家谱时最多向上追溯的代数
+	pub const MAX_FAMILY_TREE_DEPTH: u32 = 8;
+	// 通过nonfungibles的属性读写kitty元数据URI时使用的key
+	pub const METADATA_URI_KEY: &[u8] = b"uri";
 
 	pub type BalanceOf<T> =
 		<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
@@ -34,7 +69,8 @@ pub mod pallet {
 	pub trait Config: frame_system::Config {
 		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
 		// 声明一个Randomness的实现，满足Hash和BlockNumber做为类型参数
-		type Randomness: Randomness<Self::Hash, Self::BlockNumber>;
+		// 当前区块没有可用的随机数时返回None，这时延迟铸造推迟到之后有随机数的区块揭示
+		type Randomness: Randomness<Option<Self::Hash>, Self::BlockNumber>;
 		// 引入代币的关联类型
 		type Currency: Currency<Self::AccountId> + ReservableCurrency<Self::AccountId>;
 		// 在runtime中定义kittyIndex
@@ -50,10 +86,51 @@ pub mod pallet {
 
 		// 质押费用
 		type ReservationFee: Get<BalanceOf<Self>>;
+
+		// 挂单时卖家需要质押的金额，挂单被取消、成交或者失效时退回
+		type ListingDeposit: Get<BalanceOf<Self>>;
+		// 交易市场的手续费比例，从成交价格中扣除
+		type MarketplaceFee: Get<Permill>;
+		// 收取交易市场手续费的账户
+		type TreasuryAccount: Get<Self::AccountId>;
+		// 各个交易的weight，目前是人工估算的数值，见weights.rs
+		type WeightInfo: WeightInfo;
+		// 同一个区块最多结束的拍卖数量，限制结算拍卖的hook的权重
+		#[pallet::constant]
+		type MaxAuctionsPerBlock: Get<u32>;
+		// 繁殖的基础冷却区块数，第n代kitty繁殖后需要等待(n + 1)倍的基础冷却时间
+		#[pallet::constant]
+		type BreedingCooldown: Get<Self::BlockNumber>;
+		// 延迟铸造时，从请求铸造到揭示dna之间的区块数
+		#[pallet::constant]
+		type MintDelay: Get<Self::BlockNumber>;
+		// 同一个区块最多揭示的延迟铸造数量，限制揭示dna的hook的权重
+		#[pallet::constant]
+		type MaxPendingMintsPerBlock: Get<u32>;
+
+		// 用于识别跨链消息的来源(兄弟平行链)
+		type Origin: From<<Self as SystemConfig>::Origin>
+			+ Into<Result<CumulusOrigin, <Self as Config>::Origin>>;
+		// 跨链消息中调用的Call类型，这里假设兄弟平行链使用相同的Call类型
+		type Call: From<Call<Self>> + Encode;
+		// 发送跨链消息的模块
+		type XcmSender: SendXcm;
+		// 跨链转移kitty时，Transact最多可以使用的weight
+		type XcmWeightAtMost: Get<u64>;
+		// 跨链转移kitty的目标兄弟平行链，只接受来自该平行链的kitty
+		type KittyDestination: Get<ParaId>;
+
+		// kitty元数据URI的最大长度
+		#[pallet::constant]
+		type MaxMetadataUriLen: Get<u32>;
 	}
 
+	// 当前的存储版本，版本1将KittyMarket的值从价格改为Listing
+	pub(crate) const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	#[pallet::without_storage_info]
 	pub struct Pallet<T>(_);
 
@@ -70,6 +147,50 @@ pub mod pallet {
 		SeltKitty(T::KittyIndex, T::AccountId, T::AccountId),
 		// 已经移除kitty
 		RemovedKitty(T::AccountId, T::KittyIndex),
+		// 取消挂单的kittyid
+		ListingCancelled(T::KittyIndex),
+		// 成交的kittyid，交易市场收取的手续费
+		MarketplaceFeePaid(T::KittyIndex, BalanceOf<T>),
+		// 发起拍卖的kittyid，卖家，保留价，结束区块
+		AuctionCreated(T::KittyIndex, T::AccountId, BalanceOf<T>, T::BlockNumber),
+		// 出价的kittyid，买家，出价金额
+		BidPlaced(T::KittyIndex, T::AccountId, BalanceOf<T>),
+		// 拍卖成交的kittyid，卖家，买家，成交价格
+		AuctionSettled(T::KittyIndex, T::AccountId, T::AccountId, BalanceOf<T>),
+		// 没有人出价而流拍的kittyid，kitty仍然属于卖家
+		AuctionExpired(T::KittyIndex),
+		// 最高出价者的质押不足以支付出价，拍卖失败：kittyid，最高出价者，kitty仍然属于卖家
+		AuctionFailed(T::KittyIndex, T::AccountId),
+		// 繁殖者，child kittyid，父kittyid，母kittyid
+		KittyBred(T::AccountId, T::KittyIndex, T::KittyIndex, T::KittyIndex),
+		// kittyid，被授予繁殖权的账户
+		SireRightsGranted(T::KittyIndex, T::AccountId),
+		// kittyid，被撤销繁殖权的账户
+		SireRightsRevoked(T::KittyIndex, T::AccountId),
+		// 请求延迟铸造的账户，揭示dna的区块
+		MintRequested(T::AccountId, T::BlockNumber),
+		// 繁殖的父母在揭示前被移除，延迟铸造失败并退回质押的账户
+		MintFailed(T::AccountId),
+		// 原拥有者，kittyid，目标平行链，目标平行链上的接收者
+		KittySentCrossChain(T::AccountId, T::KittyIndex, ParaId, T::AccountId),
+		// 来源平行链，来源平行链上的kittyid，本链铸造的包装kittyid
+		WrappedKittyMinted(ParaId, T::KittyIndex, T::KittyIndex),
+		// 包装kitty铸造失败，来源平行链，来源平行链上的kittyid
+		WrappedKittyMintFailed(ParaId, T::KittyIndex),
+		// 解锁的kittyid，解锁后的拥有者
+		KittyUnlocked(T::KittyIndex, T::AccountId),
+		// kitty返回本链时无法为beneficiary质押，kitty保持锁定直到beneficiary领取：kittyid，beneficiary
+		KittyAwaitingClaim(T::KittyIndex, T::AccountId),
+		// 收到的解锁消息与本链锁定的kitty不一致，来源平行链，kittyid
+		UnknownKittyUnlock(ParaId, T::KittyIndex),
+		// 跨链消息发送失败，目标平行链，kittyid
+		ErrorSendingKitty(SendError, ParaId, T::KittyIndex),
+		// kittyid，新的元数据URI，None表示清除
+		MetadataUriSet(T::KittyIndex, Option<Vec<u8>>),
+		// kitty的拥有者，被授权的账户，None表示取消授权，kittyid
+		Approval(T::AccountId, Option<T::AccountId>, T::KittyIndex),
+		// kitty的拥有者，操作者，是否授权
+		ApprovalForAll(T::AccountId, T::AccountId, bool),
 	}
 
 	/// 定义存储
@@ -95,11 +216,11 @@ pub mod pallet {
 		StorageMap<_, Blake2_128Concat, T::KittyIndex, Option<T::AccountId>, ValueQuery>;
 
 	/// 4.定义一个kitty交易市场，用来对需要卖的kitty进行挂单
-	/// kittyindex为key,挂单的金额为value
+	/// kittyindex为key,挂单信息(卖家、价格、质押)为value
 	#[pallet::storage]
 	#[pallet::getter(fn kittymarket)]
 	pub type KittyMarket<T: Config> =
-		StorageMap<_, Blake2_128Concat, T::KittyIndex, BalanceOf<T>, ValueQuery>;
+		StorageMap<_, Blake2_128Concat, T::KittyIndex, Listing<T::AccountId, BalanceOf<T>>>;
 
 	/// 5.定义一个存储，能够通过指定的账户查询到其拥有的所有kittyId
 	#[pallet::storage]
@@ -107,6 +228,118 @@ pub mod pallet {
 	pub type KittyBabies<T: Config> =
 		StorageMap<_, Blake2_128Concat, T::AccountId, Vec<T::KittyIndex>, ValueQuery>;
 
+	/// 6.正在拍卖的kitty
+	/// kittyIndex为key，拍卖信息为value
+	#[pallet::storage]
+	#[pallet::getter(fn auctions)]
+	pub type Auctions<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::KittyIndex,
+		Auction<T::AccountId, BalanceOf<T>, T::BlockNumber>,
+	>;
+
+	/// 8.kitty的血统信息，包括父母、代数、出生区块以及繁殖冷却
+	/// 引入血统之前创建的kitty没有记录，视为第0代
+	#[pallet::storage]
+	#[pallet::getter(fn genealogy)]
+	pub type Genealogy<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::KittyIndex,
+		KittyGenealogy<T::KittyIndex, T::BlockNumber>,
+	>;
+
+	/// 9.kitty的拥有者授予其他账户的繁殖权，kitty的所有权转移后失效
+	#[pallet::storage]
+	pub type SireRights<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::KittyIndex,
+		Blake2_128Concat,
+		T::AccountId,
+		(),
+	>;
+
+	/// 10.每个区块需要揭示dna的延迟铸造请求，包括创建和繁殖
+	/// 请求时还不知道揭示区块的随机数，因此请求者和出块节点都无法预先选择dna
+	#[pallet::storage]
+	pub type PendingMints<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::BlockNumber,
+		BoundedVec<PendingMint<T::AccountId, T::KittyIndex>, T::MaxPendingMintsPerBlock>,
+		ValueQuery,
+	>;
+
+	/// 18.下一个需要揭示延迟铸造的区块
+	/// 没有随机数时揭示被推迟，之后的区块从这里继续揭示，没有被推迟时为当前区块的下一个区块
+	#[pallet::storage]
+	pub type NextRevealBlock<T: Config> = StorageValue<_, T::BlockNumber>;
+
+	/// 11.跨链转移到兄弟平行链而锁定的kitty，value为目标平行链
+	/// 锁定的kitty不能转移、交易、拍卖、繁殖或者移除，直到从目标平行链返回
+	#[pallet::storage]
+	#[pallet::getter(fn locked_kitties)]
+	pub type LockedKitties<T: Config> = StorageMap<_, Blake2_128Concat, T::KittyIndex, ParaId>;
+
+	/// 12.从兄弟平行链转移过来的包装kitty，value为(来源平行链, 来源平行链上的kittyId)
+	#[pallet::storage]
+	#[pallet::getter(fn wrapped_kitties)]
+	pub type WrappedKitties<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::KittyIndex, (ParaId, T::KittyIndex)>;
+
+	/// 13.kitty的元数据URI，供通用的NFT工具读取
+	#[pallet::storage]
+	#[pallet::getter(fn metadata_uri)]
+	pub type KittyMetadataUri<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::KittyIndex, BoundedVec<u8, T::MaxMetadataUriLen>>;
+
+	/// 14.kitty的拥有者授权可以转移该kitty的账户，kitty的所有权转移后失效
+	#[pallet::storage]
+	#[pallet::getter(fn approved)]
+	pub type KittyApprovals<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::KittyIndex, T::AccountId>;
+
+	/// 15.账户授权的操作者，操作者可以转移该账户的所有kitty
+	#[pallet::storage]
+	pub type KittyOperators<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		T::AccountId,
+		(),
+	>;
+
+	/// 16.跨链返回时无法为beneficiary质押而等待领取的kitty，value为beneficiary
+	/// 等待领取的kitty仍然被锁定并属于原拥有者，beneficiary质押后通过claim_kitty领取
+	#[pallet::storage]
+	#[pallet::getter(fn unclaimed_kitties)]
+	pub type UnclaimedKitties<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::KittyIndex, T::AccountId>;
+
+	/// 17.交易市场的有序索引，用于按游标分页查询，value为(kittyId, 挂单价格)
+	/// key为(排序方式, 排序键)，排序键由大端编码的价格和kittyId组成，倒序时按位取反，
+	/// 所以同一种排序方式下的挂单按存储key的顺序排列，分页时从游标对应的key继续遍历
+	#[pallet::storage]
+	pub type ListingIndex<T: Config> = StorageMap<
+		_,
+		Identity,
+		(MarketSortOrder, [u8; 24]),
+		(T::KittyIndex, BalanceOf<T>),
+	>;
+
+	/// 7.每个区块结束的拍卖，用于在区块开始时结算拍卖
+	#[pallet::storage]
+	pub type AuctionsEndingAt<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::BlockNumber,
+		BoundedVec<T::KittyIndex, T::MaxAuctionsPerBlock>,
+		ValueQuery,
+	>;
+
 	#[pallet::error]
 	pub enum Error<T> {
 		/// kittyId溢出错误
@@ -119,55 +352,136 @@ pub mod pallet {
 		InvalidKittyIndex,
 		/// 没有足够的质押金额
 		NoSufficientBalance,
+		/// kitty没有在交易市场挂单
+		NotForSale,
+		/// 不能购买自己挂单的kitty
+		BuyOwnKitty,
+		/// 挂单价格高于买家愿意支付的最高价格
+		PriceTooHigh,
+		/// kitty已经在交易市场挂单
+		AlreadyListed,
+		/// kitty正在拍卖
+		KittyInAuction,
+		/// kitty没有在拍卖
+		AuctionNotFound,
+		/// 拍卖的结束区块必须晚于当前区块
+		InvalidAuctionEnd,
+		/// 拍卖已经结束
+		AuctionEnded,
+		/// 出价低于保留价或者最低加价
+		BidTooLow,
+		/// 结束区块的拍卖数量已满
+		TooManyAuctions,
+		/// 既不是kitty的拥有者，也没有被授予繁殖权
+		NoBreedingRights,
+		/// kitty还在繁殖冷却中
+		KittyOnCooldown,
+		/// 揭示区块的延迟铸造请求数量已满
+		TooManyPendingMints,
+		/// kitty已经跨链转移而被锁定
+		KittyLocked,
+		/// 跨链消息发送失败
+		XcmSendError,
+		/// 元数据URI超过最大长度
+		MetadataUriTooLong,
+		/// 没有转移该kitty的授权
+		NotApproved,
+		/// 不能授权给自己
+		ApproveToSelf,
+		/// kitty没有等待领取
+		NothingToClaim,
+		/// 不是等待领取的kitty的beneficiary
+		NotBeneficiary,
+		/// kitty没有被跨链锁定
+		KittyNotLocked,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		// 在区块开始时结算在该区块结束的拍卖
+		fn on_initialize(n: T::BlockNumber) -> Weight {
+			let ending = AuctionsEndingAt::<T>::take(n);
+			let count = ending.len() as Weight;
+			for kitty_id in ending {
+				Self::settle_auction(kitty_id);
+			}
+			// 在on_finalize中揭示的延迟铸造，每个铸造读写Kitties、Owner、KittyBabies、Genealogy
+			// 繁殖的铸造还需要读取父母的Kitties和Genealogy
+			let (blocks, _) = Self::reveal_blocks(n);
+			let mints = blocks
+				.iter()
+				.map(|block| PendingMints::<T>::decode_len(block).unwrap_or(0) as Weight)
+				.sum::<Weight>();
+			let blocks = blocks.len() as Weight;
+			// 读写一次AuctionsEndingAt，每个拍卖最多读写Auctions、Owner、KittyBabies以及买卖双方和手续费账户
+			// 读写一次NextRevealBlock，每个揭示的区块读写一次PendingMints
+			T::DbWeight::get().reads_writes(
+				3 + blocks + count * 6 + mints * 7,
+				3 + blocks + count * 7 + mints * 5,
+			)
+		}
+
+		// 在区块结束时揭示延迟铸造的dna，此时已经可以读取本区块的中继链数据
+		// 没有可用的随机数时不揭示，请求留在PendingMints中，由之后的区块从NextRevealBlock继续揭示
+		fn on_finalize(n: T::BlockNumber) {
+			let (blocks, next) = Self::reveal_blocks(n);
+			let random = match T::Randomness::random(b"kitties/mint").0 {
+				Some(random) => random,
+				None => {
+					// blocks中的第一个区块就是最早还没有揭示的区块
+					NextRevealBlock::<T>::put(blocks[0]);
+					return
+				},
+			};
+
+			for block in blocks {
+				for (index, pending) in PendingMints::<T>::take(block).into_iter().enumerate() {
+					Self::reveal_mint(pending, &random, block, index as u32);
+				}
+			}
+			NextRevealBlock::<T>::put(next);
+		}
+
+		fn integrity_test() {
+			// 揭示区块必须在请求区块之后，否则请求时就已经知道揭示用的随机数
+			assert!(!T::MintDelay::get().is_zero(), "MintDelay must not be zero");
+		}
+
+		fn on_runtime_upgrade() -> Weight {
+			crate::migrations::v1::migrate::<T>()
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			Self::do_try_state()
+		}
 	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 		/// 创建kitty
-		#[pallet::weight(0)]
+		/// 和request_mint一样延迟铸造，kitty在MintDelay个区块之后铸造
+		#[pallet::weight(T::WeightInfo::create())]
 		pub fn create(origin: OriginFor<T>) -> DispatchResult {
 			// 检查调用者的身份
 			let who = ensure_signed(origin)?;
 
-			// 创建kitty之前质押一定的金额
-			T::Currency::reserve(&who, T::ReservationFee::get())
-				.map_err(|_| Error::<T>::NoSufficientBalance)?;
-
-			// 1. kitty的id
-			// kitties_count()是获取的下一个kitty的id,它是用于获取上面KittiesCount存储项的getter函数
-			let kitty_id: <T as Config>::KittyIndex = match Self::kitties_count() {
-				Some(id) => {
-					// 如果当前kittyId的id已经超过了KittyIndex的最大值(u32最大值)，说明就无法再创建新的Index，就报溢出错误
-					ensure!(id != T::KittyIndex::max_value(), Error::<T>::KittiesCountOverflow);
-					// 否则就从当前id开始
-					id
-				},
-				// 如果没有获取到，就从0开始
-				None => Zero::zero(),
-			};
-
-			// 2. kitty的data
-			// 通过随机的方式来获取,通过random_value方法来获取，其实现在impl中
-			let dna = Self::random_value(&who);
-
-			// 3. 更新三个Storage的存储信息，分别是kitty数据，kitty所有者，kitty的数量
-			Kitties::<T>::insert(kitty_id, Some(Kitty(dna)));
-			Owner::<T>::insert(kitty_id, Some(who.clone()));
-			KittiesCount::<T>::put(kitty_id + One::one()); // 更新下一个kitty的index，即+1
-
-			// 更新账户拥有的kittyId列表
-			Self::push_kitty_babies_list(&who.clone(), kitty_id);
+			Self::request_pending_mint(who, None)
+		}
 
-			// 4. 抛出event(已经创建了新的kitty)
-			Self::deposit_event(Event::KittyCreate(who, kitty_id));
+		/// 请求延迟铸造kitty，dna在MintDelay个区块之后由当时的随机数决定
+		/// 请求时分配kittyId并质押ReservationFee
+		#[pallet::weight(T::WeightInfo::request_mint())]
+		pub fn request_mint(origin: OriginFor<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
 
-			Ok(().into())
+			Self::request_pending_mint(who, None)
 		}
 
 		/// 转移Kitty的所有者
 		/// para1： 新的owner
 		/// para2:  需要转移的kitty_id
-		#[pallet::weight(0)]
+		#[pallet::weight(T::WeightInfo::transfer())]
 		pub fn transfer(
 			origin: OriginFor<T>,
 			new_owner: T::AccountId,
@@ -177,30 +491,13 @@ pub mod pallet {
 			// 核查当前的调用kittyid的人是否是它的owner，如果不是就没有权限进行调用
 			ensure!(Some(who.clone()) == Owner::<T>::get(kitty_id), Error::<T>::NotOwner);
 
-			// 新的拥有者需要质押一定的金额
-			T::Currency::reserve(&new_owner.clone(), T::ReservationFee::get())
-				.map_err(|_| Error::<T>::NoSufficientBalance)?;
-
-			// 插入新的Owner
-			Owner::<T>::insert(kitty_id, Some(new_owner.clone()));
-
-			// 更新新的owner之后,退回原拥有者的质押金额
-			T::Currency::unreserve(&who, T::ReservationFee::get());
-
-			// 将kitty_id从old owner的kittyId列表中移除
-			Self::remove_kittyid_from_kitty_babies_list(&who.clone(), kitty_id.clone());
-
-			// 更新新owner的kittyId列表
-			Self::push_kitty_babies_list(&new_owner.clone(), kitty_id);
-
-			Self::deposit_event(Event::KittyTransfer(who, new_owner, kitty_id));
-			Ok(())
+			Self::do_transfer(who, new_owner, kitty_id)
 		}
 
 		/// 繁殖kitty
 		/// para1: 父kitty
 		/// para2: 母kitty
-		#[pallet::weight(0)]
+		#[pallet::weight(T::WeightInfo::breed())]
 		pub fn breed(
 			origin: OriginFor<T>,
 			kitty_id_1: T::KittyIndex,
@@ -212,49 +509,63 @@ pub mod pallet {
 			ensure!(kitty_id_1 != kitty_id_2, Error::<T>::SameParentIndex);
 
 			// 确保两个kittyId是否已经有数据了，如果没有就抛出异常
-			let kitty1 = Self::kitties(kitty_id_1).ok_or(Error::<T>::InvalidKittyIndex)?;
-			let kitty2 = Self::kitties(kitty_id_2).ok_or(Error::<T>::InvalidKittyIndex)?;
+			ensure!(Self::kitties(kitty_id_1).is_some(), Error::<T>::InvalidKittyIndex);
+			ensure!(Self::kitties(kitty_id_2).is_some(), Error::<T>::InvalidKittyIndex);
 
-			// 得到child kitty的Id
-			let child_kitty_id = match Self::kitties_count() {
-				Some(id) => {
-					// 如果当前kittyId的id已经超过了KittyIndex的最大值(u32最大值)，说明就无法再创建新的Index，就报溢出错误
-					ensure!(id != T::KittyIndex::max_value(), Error::<T>::KittiesCountOverflow);
-					// 否则就从当前id开始
-					id
-				},
-				// 如果没有获取到，就从0开始
-				None => Zero::zero(),
-			};
+			// 确保调用者可以使用两个kitty繁殖，并且两个kitty都不在冷却中
+			let now = <frame_system::Pallet<T>>::block_number();
+			let mut genealogy_1 = Self::ensure_can_breed(&who, kitty_id_1, now)?;
+			let mut genealogy_2 = Self::ensure_can_breed(&who, kitty_id_2, now)?;
 
-			// 根据两个Parent的DNA进行混淆，产生新的child的DNA
-			let dna_1 = kitty1.0;
-			let dna_2 = kitty2.0;
+			// 和创建kitty一样，繁殖出的child kitty也需要质押，并且延迟铸造
+			// child的DNA在揭示区块根据当时的随机数从两个Parent的DNA按照显性、隐性基因的遗传规则产生
+			Self::request_pending_mint(who, Some((kitty_id_1, kitty_id_2)))?;
 
-			// 根据调用者的账户身份产生一个random作为一个Selector
-			let selector = Self::random_value(&who);
-			let mut new_dna = [0u8; 16];
+			// 父母进入冷却，代数越大冷却时间越长
+			genealogy_1.cooldown_until = now.saturating_add(Self::breeding_cooldown(genealogy_1.generation));
+			genealogy_2.cooldown_until = now.saturating_add(Self::breeding_cooldown(genealogy_2.generation));
+			Genealogy::<T>::insert(kitty_id_1, genealogy_1);
+			Genealogy::<T>::insert(kitty_id_2, genealogy_2);
 
-			for i in 0..dna_1.len() {
-				// 当selector的位为1的时候就使用dna_1的值，当selector的位为0的时候，就使用dna_2的值，作为新的dna值（通过位运算）
-				new_dna[i] = (selector[i] & dna_1[i]) | (!selector[i] & dna_2[i]);
-			}
+			Ok(())
+		}
+
+		/// 授予其他账户使用自己的kitty繁殖的权利
+		#[pallet::weight(T::WeightInfo::grant_sire_rights())]
+		pub fn grant_sire_rights(
+			origin: OriginFor<T>,
+			kitty_id: T::KittyIndex,
+			grantee: T::AccountId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Some(who) == Owner::<T>::get(kitty_id), Error::<T>::NotOwner);
+			ensure!(!LockedKitties::<T>::contains_key(kitty_id), Error::<T>::KittyLocked);
 
-			// 更新 child Kitty 的存储
-			Kitties::<T>::insert(child_kitty_id, Some(Kitty(new_dna)));
-			Owner::<T>::insert(child_kitty_id, Some(who.clone()));
-			KittiesCount::<T>::put(child_kitty_id + One::one());
+			SireRights::<T>::insert(kitty_id, &grantee, ());
 
-			// 更新当前用户的kittyId列表
-			Self::push_kitty_babies_list(&who.clone(), child_kitty_id);
-			// 创建成功事件
-			Self::deposit_event(Event::KittyCreate(who, child_kitty_id));
+			Self::deposit_event(Event::SireRightsGranted(kitty_id, grantee));
+			Ok(())
+		}
+
+		/// 撤销授予其他账户的繁殖权
+		#[pallet::weight(T::WeightInfo::revoke_sire_rights())]
+		pub fn revoke_sire_rights(
+			origin: OriginFor<T>,
+			kitty_id: T::KittyIndex,
+			grantee: T::AccountId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Some(who) == Owner::<T>::get(kitty_id), Error::<T>::NotOwner);
 
+			SireRights::<T>::remove(kitty_id, &grantee);
+
+			Self::deposit_event(Event::SireRightsRevoked(kitty_id, grantee));
 			Ok(())
 		}
 
 		/// 卖Kitty，将需要卖的kittyIndex放进kittymarket进行挂单
-		#[pallet::weight(0)]
+		/// 第一次挂单时质押ListingDeposit，已经挂单时只更新价格
+		#[pallet::weight(T::WeightInfo::sell_kitties())]
 		pub fn sell_kitties(
 			origin: OriginFor<T>,
 			kitty_id: T::KittyIndex,
@@ -263,37 +574,78 @@ pub mod pallet {
 			let who = ensure_signed(origin)?;
 
 			// 确保挂单的人是Kitty的拥有者
-			ensure!(Some(who) == <Owner<T>>::get(kitty_id), <Error<T>>::NotOwner);
+			ensure!(Some(who.clone()) == <Owner<T>>::get(kitty_id), <Error<T>>::NotOwner);
+			// 正在拍卖的kitty不能挂单
+			ensure!(!<Auctions<T>>::contains_key(kitty_id), <Error<T>>::KittyInAuction);
+			ensure!(!<LockedKitties<T>>::contains_key(kitty_id), <Error<T>>::KittyLocked);
+
+			let deposit = match <KittyMarket<T>>::get(kitty_id) {
+				Some(listing) => {
+					Self::unindex_listing(kitty_id, listing.price);
+					listing.deposit
+				},
+				None => {
+					let deposit = T::ListingDeposit::get();
+					T::Currency::reserve(&who, deposit)
+						.map_err(|_| Error::<T>::NoSufficientBalance)?;
+					deposit
+				},
+			};
 
-			// 将kittyid以及对应的金额挂单到kitty的交易市场
-			<KittyMarket<T>>::insert(kitty_id, selling_value);
+			// 将kittyid以及对应的挂单信息放到kitty的交易市场
+			<KittyMarket<T>>::insert(
+				kitty_id,
+				Listing { seller: who, price: selling_value, deposit },
+			);
+			Self::index_listing(kitty_id, selling_value);
 
 			Self::deposit_event(Event::SellingKitty(kitty_id, selling_value));
 			Ok(())
 		}
 
-		/// 买kitty，从kittymarket接单，买入
-		#[pallet::weight(0)]
-		pub fn buy_kitties(origin: OriginFor<T>, kitty_id: T::KittyIndex) -> DispatchResult {
-			let new_owner = ensure_signed(origin)?;
+		/// 取消挂单，退回挂单时的质押
+		#[pallet::weight(T::WeightInfo::cancel_listing())]
+		pub fn cancel_listing(origin: OriginFor<T>, kitty_id: T::KittyIndex) -> DispatchResult {
+			let who = ensure_signed(origin)?;
 
-			// 判断需要购买的kitty_id在kitty市场中是否存在，如果指定的kitty的价格为0，那么就不存在
-			ensure!(<KittyMarket<T>>::get(kitty_id) != Zero::zero(), <Error<T>>::InvalidKittyIndex);
+			let listing = <KittyMarket<T>>::get(kitty_id).ok_or(<Error<T>>::NotForSale)?;
+			ensure!(listing.seller == who, <Error<T>>::NotOwner);
 
-			// 获取kitty交易市场中指定kittyindex的挂单价格
-			let kitty_price = <KittyMarket<T>>::get(kitty_id);
+			Self::remove_listing(kitty_id);
 
-			// 获取kitty_id的拥有者(因为Owner中的值为option类型，所以需要unwrap一下)
-			let old_owner = <Owner<T>>::get(kitty_id).unwrap();
+			Self::deposit_event(Event::ListingCancelled(kitty_id));
+			Ok(())
+		}
 
-			// 判断当前账户的余额是否大于需要质押的金额，如果不是，就报错
-			ensure!(
-				T::Currency::free_balance(&new_owner) > kitty_price,
-				<Error<T>>::NoSufficientBalance
-			);
+		/// 买kitty，从kittymarket接单，买入
+		/// max_price为买家愿意支付的最高价格，防止卖家在成交前抬高价格
+		#[pallet::weight(T::WeightInfo::buy_kitties())]
+		#[transactional]
+		pub fn buy_kitties(
+			origin: OriginFor<T>,
+			kitty_id: T::KittyIndex,
+			max_price: BalanceOf<T>,
+		) -> DispatchResult {
+			let new_owner = ensure_signed(origin)?;
+
+			// 判断需要购买的kitty_id在kitty市场中是否存在
+			let listing = <KittyMarket<T>>::get(kitty_id).ok_or(<Error<T>>::NotForSale)?;
+			// 挂单的卖家必须仍然是kitty的拥有者
+			let old_owner = <Owner<T>>::get(kitty_id).ok_or(<Error<T>>::InvalidKittyIndex)?;
+			ensure!(listing.seller == old_owner, <Error<T>>::NotForSale);
+			ensure!(new_owner != old_owner, <Error<T>>::BuyOwnKitty);
+			ensure!(listing.price <= max_price, <Error<T>>::PriceTooHigh);
+
+			// 买家支付挂单价格，其中手续费转给交易市场的账户，其余转给卖家
+			let fee = Self::marketplace_fee(listing.price);
+			T::Currency::transfer(&new_owner, &old_owner, listing.price - fee, KeepAlive)?;
+			if !fee.is_zero() {
+				T::Currency::transfer(&new_owner, &T::TreasuryAccount::get(), fee, KeepAlive)?;
+			}
 
-			// 将当前新的账户扣除挂单的金额给原始账户，用于购买kitty
-			T::Currency::transfer(&old_owner, &new_owner, kitty_price, KeepAlive)?;
+			// 新的拥有者需要质押一定的金额
+			T::Currency::reserve(&new_owner, T::ReservationFee::get())
+				.map_err(|_| Error::<T>::NoSufficientBalance)?;
 
 			// 并将kitty给新的owner
 			<Owner<T>>::insert(kitty_id, Some(new_owner.clone()));
@@ -307,47 +659,870 @@ pub mod pallet {
 			// 将该kitty_id的原有账户释放质押金额
 			T::Currency::unreserve(&old_owner, T::ReservationFee::get());
 
-			// 此时就已经完成了交易市场中的kitty的交易，将该id从交易市场中剔除
-			<KittyMarket<T>>::remove(kitty_id);
+			// 此时就已经完成了交易市场中的kitty的交易，将该id从交易市场中剔除，并退回挂单的质押
+			Self::remove_listing(kitty_id);
+			Self::clear_sire_rights(kitty_id);
+			Self::clear_approval(&old_owner, kitty_id);
 
+			if !fee.is_zero() {
+				Self::deposit_event(Event::MarketplaceFeePaid(kitty_id, fee));
+			}
 			Self::deposit_event(Event::SeltKitty(kitty_id, old_owner, new_owner));
 
 			Ok(())
 		}
 
-		// 移除kitty
-		#[pallet::weight(0)]
+		/// 发起英式拍卖，拍卖在end_block开始时结算
+		/// 出价最高且不低于保留价的买家得到kitty，没有人出价时kitty仍然属于卖家
+		#[pallet::weight(T::WeightInfo::create_auction())]
+		pub fn create_auction(
+			origin: OriginFor<T>,
+			kitty_id: T::KittyIndex,
+			reserve_price: BalanceOf<T>,
+			min_increment: BalanceOf<T>,
+			end_block: T::BlockNumber,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			ensure!(Some(who.clone()) == <Owner<T>>::get(kitty_id), <Error<T>>::NotOwner);
+			ensure!(!<Auctions<T>>::contains_key(kitty_id), <Error<T>>::KittyInAuction);
+			ensure!(!<LockedKitties<T>>::contains_key(kitty_id), <Error<T>>::KittyLocked);
+			// 挂单的kitty需要先取消挂单才能拍卖
+			ensure!(!<KittyMarket<T>>::contains_key(kitty_id), <Error<T>>::AlreadyListed);
+			ensure!(
+				end_block > <frame_system::Pallet<T>>::block_number(),
+				<Error<T>>::InvalidAuctionEnd
+			);
+
+			<AuctionsEndingAt<T>>::try_mutate(end_block, |ending| ending.try_push(kitty_id))
+				.map_err(|_| <Error<T>>::TooManyAuctions)?;
+			<Auctions<T>>::insert(
+				kitty_id,
+				Auction {
+					seller: who.clone(),
+					reserve_price,
+					min_increment,
+					end_block,
+					highest_bid: None,
+				},
+			);
+
+			Self::deposit_event(Event::AuctionCreated(kitty_id, who, reserve_price, end_block));
+			Ok(())
+		}
+
+		/// 对正在拍卖的kitty出价
+		/// 出价金额以及成交后kitty的质押金额会被质押，被更高的出价超过时退回
+		#[pallet::weight(T::WeightInfo::bid())]
+		#[transactional]
+		pub fn bid(
+			origin: OriginFor<T>,
+			kitty_id: T::KittyIndex,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			let bidder = ensure_signed(origin)?;
+
+			let mut auction = <Auctions<T>>::get(kitty_id).ok_or(<Error<T>>::AuctionNotFound)?;
+			ensure!(
+				<frame_system::Pallet<T>>::block_number() < auction.end_block,
+				<Error<T>>::AuctionEnded
+			);
+			ensure!(bidder != auction.seller, <Error<T>>::BuyOwnKitty);
+			ensure!(amount >= auction.reserve_price, <Error<T>>::BidTooLow);
+			// 新的出价必须严格高于当前的最高出价，并且加价不低于最低加价
+			if let Some((_, highest)) = &auction.highest_bid {
+				ensure!(
+					amount > *highest && amount >= highest.saturating_add(auction.min_increment),
+					<Error<T>>::BidTooLow
+				);
+			}
+
+			T::Currency::reserve(&bidder, amount.saturating_add(T::ReservationFee::get()))
+				.map_err(|_| <Error<T>>::NoSufficientBalance)?;
+			// 退回之前最高出价的买家的质押
+			if let Some((previous, highest)) = auction.highest_bid.take() {
+				T::Currency::unreserve(&previous, highest.saturating_add(T::ReservationFee::get()));
+			}
+			auction.highest_bid = Some((bidder.clone(), amount));
+			<Auctions<T>>::insert(kitty_id, auction);
+
+			Self::deposit_event(Event::BidPlaced(kitty_id, bidder, amount));
+			Ok(())
+		}
+
+		// 移除kitty，只有kitty的拥有者可以移除
+		// 移除后退回创建kitty时的质押，KittiesCount是下一个kitty的id，不会减少
+		#[pallet::weight(T::WeightInfo::remove_kitty())]
 		pub fn remove_kitty(
 			origin: OriginFor<T>,
 			kitty_id: T::KittyIndex,
 		) -> DispatchResult{
 			let owner = ensure_signed(origin)?;
-			// 如果在交易市场的话，首先从交易市场移除
-			if <KittyMarket<T>>::contains_key(kitty_id) {
-				<KittyMarket<T>>::remove(kitty_id);
-			}else{
-				()
-			}
-			<Kitties<T>>::remove(kitty_id);
+			ensure!(Some(owner.clone()) == <Owner<T>>::get(kitty_id), <Error<T>>::NotOwner);
+			// 正在拍卖的kitty不能移除，否则无法退回买家的出价
+			ensure!(!<Auctions<T>>::contains_key(kitty_id), <Error<T>>::KittyInAuction);
+			ensure!(!<LockedKitties<T>>::contains_key(kitty_id), <Error<T>>::KittyLocked);
+
+			Self::burn(&owner, kitty_id);
+
 			Self::deposit_event(Event::RemovedKitty(owner, kitty_id));
 			Ok(())
 		}
+
+		/// 设置kitty的元数据URI，uri为None时清除
+		#[pallet::weight(T::WeightInfo::set_metadata_uri())]
+		pub fn set_metadata_uri(
+			origin: OriginFor<T>,
+			kitty_id: T::KittyIndex,
+			uri: Option<Vec<u8>>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Some(who) == <Owner<T>>::get(kitty_id), <Error<T>>::NotOwner);
+			ensure!(!<LockedKitties<T>>::contains_key(kitty_id), <Error<T>>::KittyLocked);
+
+			Self::do_set_metadata_uri(kitty_id, uri)
+		}
+
+		/// 授权一个账户转移kitty，approved为None时取消授权
+		/// kitty的拥有者以及拥有者的操作者可以授权，每个kitty同时只能授权一个账户
+		#[pallet::weight(T::WeightInfo::approve())]
+		pub fn approve(
+			origin: OriginFor<T>,
+			kitty_id: T::KittyIndex,
+			approved: Option<T::AccountId>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let owner = <Owner<T>>::get(kitty_id).ok_or(<Error<T>>::InvalidKittyIndex)?;
+			ensure!(
+				who == owner || Self::is_operator(&owner, &who),
+				<Error<T>>::NotOwner
+			);
+			ensure!(!<LockedKitties<T>>::contains_key(kitty_id), <Error<T>>::KittyLocked);
+
+			match &approved {
+				Some(account) => {
+					ensure!(*account != owner, <Error<T>>::ApproveToSelf);
+					<KittyApprovals<T>>::insert(kitty_id, account);
+				},
+				None => <KittyApprovals<T>>::remove(kitty_id),
+			}
+
+			Self::deposit_event(Event::Approval(owner, approved, kitty_id));
+			Ok(())
+		}
+
+		/// 授权或者取消操作者转移调用者的所有kitty，包括之后获得的kitty
+		#[pallet::weight(T::WeightInfo::set_approval_for_all())]
+		pub fn set_approval_for_all(
+			origin: OriginFor<T>,
+			operator: T::AccountId,
+			approved: bool,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(who != operator, <Error<T>>::ApproveToSelf);
+
+			if approved {
+				<KittyOperators<T>>::insert(&who, &operator, ());
+			} else {
+				<KittyOperators<T>>::remove(&who, &operator);
+			}
+
+			Self::deposit_event(Event::ApprovalForAll(who, operator, approved));
+			Ok(())
+		}
+
+		/// 转移from的kitty给to
+		/// 调用者必须是kitty的拥有者、被授权的账户或者拥有者的操作者
+		#[pallet::weight(T::WeightInfo::transfer_from())]
+		pub fn transfer_from(
+			origin: OriginFor<T>,
+			from: T::AccountId,
+			to: T::AccountId,
+			kitty_id: T::KittyIndex,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Some(from.clone()) == <Owner<T>>::get(kitty_id), <Error<T>>::NotOwner);
+			ensure!(
+				who == from ||
+					<KittyApprovals<T>>::get(kitty_id) == Some(who.clone()) ||
+					Self::is_operator(&from, &who),
+				<Error<T>>::NotApproved
+			);
+
+			Self::do_transfer(from, to, kitty_id)
+		}
+
+		/// 跨链转移kitty
+		/// 本链的kitty被锁定，并在KittyDestination上铸造一个包装kitty给beneficiary
+		/// 从兄弟平行链转移过来的包装kitty被销毁，并在来源平行链上解锁原来的kitty给beneficiary
+		#[pallet::weight(T::WeightInfo::transfer_cross_chain())]
+		pub fn transfer_cross_chain(
+			origin: OriginFor<T>,
+			kitty_id: T::KittyIndex,
+			beneficiary: T::AccountId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Some(who.clone()) == <Owner<T>>::get(kitty_id), <Error<T>>::NotOwner);
+			ensure!(!<Auctions<T>>::contains_key(kitty_id), <Error<T>>::KittyInAuction);
+			ensure!(!<LockedKitties<T>>::contains_key(kitty_id), <Error<T>>::KittyLocked);
+
+			// 先发送跨链消息，发送失败时本链的kitty保持不变
+			let para = match <WrappedKitties<T>>::get(kitty_id) {
+				Some((para, remote_kitty_id)) => {
+					let call = Call::<T>::unlock_kitty {
+						kitty_id: remote_kitty_id,
+						beneficiary: Some(beneficiary.clone()),
+					};
+					Self::send_kitty_call(para, call).map_err(|_| <Error<T>>::XcmSendError)?;
+					Self::burn(&who, kitty_id);
+					para
+				},
+				None => {
+					let para = T::KittyDestination::get();
+					let kitty = <Kitties<T>>::get(kitty_id).ok_or(<Error<T>>::InvalidKittyIndex)?;
+					let call = Call::<T>::mint_wrapped {
+						remote_kitty_id: kitty_id,
+						kitty,
+						beneficiary: beneficiary.clone(),
+					};
+					Self::send_kitty_call(para, call).map_err(|_| <Error<T>>::XcmSendError)?;
+					// 锁定的kitty不再挂单，授予的繁殖权和转移授权也失效
+					Self::remove_listing(kitty_id);
+					Self::clear_sire_rights(kitty_id);
+					Self::clear_approval(&who, kitty_id);
+					<LockedKitties<T>>::insert(kitty_id, para);
+					para
+				},
+			};
+
+			Self::deposit_event(Event::KittySentCrossChain(who, kitty_id, para, beneficiary));
+			Ok(())
+		}
+
+		/// 兄弟平行链通过XCM Transact调用，为beneficiary铸造包装kitty
+		/// 铸造失败时通知来源平行链将kitty解锁给原拥有者
+		#[pallet::weight(T::WeightInfo::mint_wrapped())]
+		pub fn mint_wrapped(
+			origin: OriginFor<T>,
+			remote_kitty_id: T::KittyIndex,
+			kitty: Kitty,
+			beneficiary: T::AccountId,
+		) -> DispatchResult {
+			let para = ensure_sibling_para(<T as Config>::Origin::from(origin))?;
+			ensure!(para == T::KittyDestination::get(), DispatchError::BadOrigin);
+
+			// 包装kitty和本链的kitty一样需要质押
+			let minted = Self::next_kitty_id().and_then(|kitty_id| {
+				T::Currency::reserve(&beneficiary, T::ReservationFee::get())
+					.map_err(|_| Error::<T>::NoSufficientBalance)?;
+				Ok(kitty_id)
+			});
+
+			match minted {
+				Ok(kitty_id) => {
+					KittiesCount::<T>::put(kitty_id + One::one());
+					Self::insert_kitty(&beneficiary, kitty_id, kitty.0, None);
+					<WrappedKitties<T>>::insert(kitty_id, (para, remote_kitty_id));
+					Self::deposit_event(Event::WrappedKittyMinted(para, remote_kitty_id, kitty_id));
+				},
+				Err(_) => {
+					let call = Call::<T>::unlock_kitty { kitty_id: remote_kitty_id, beneficiary: None };
+					match Self::send_kitty_call(para, call) {
+						Ok(()) => Self::deposit_event(Event::WrappedKittyMintFailed(para, remote_kitty_id)),
+						Err(e) => Self::deposit_event(Event::ErrorSendingKitty(e, para, remote_kitty_id)),
+					}
+				},
+			}
+			Ok(())
+		}
+
+		/// 兄弟平行链通过XCM Transact调用，解锁之前跨链转移的kitty
+		/// beneficiary为None时，kitty退回给原拥有者
+		/// 无法为beneficiary质押时，kitty保持锁定，等待beneficiary通过claim_kitty领取
+		#[pallet::weight(T::WeightInfo::unlock_kitty())]
+		pub fn unlock_kitty(
+			origin: OriginFor<T>,
+			kitty_id: T::KittyIndex,
+			beneficiary: Option<T::AccountId>,
+		) -> DispatchResult {
+			let para = ensure_sibling_para(<T as Config>::Origin::from(origin))?;
+
+			// 只有kitty被转移到的平行链可以解锁kitty，已经在等待领取的kitty不能再次解锁
+			let owner = match (<LockedKitties<T>>::get(kitty_id), <Owner<T>>::get(kitty_id)) {
+				(Some(target), Some(owner))
+					if target == para && !<UnclaimedKitties<T>>::contains_key(kitty_id) =>
+					owner,
+				_ => {
+					Self::deposit_event(Event::UnknownKittyUnlock(para, kitty_id));
+					return Ok(())
+				},
+			};
+
+			match beneficiary {
+				Some(beneficiary) if beneficiary != owner => {
+					// 包装kitty已经在目标平行链销毁，不能退回给原拥有者
+					if T::Currency::reserve(&beneficiary, T::ReservationFee::get()).is_err() {
+						<UnclaimedKitties<T>>::insert(kitty_id, &beneficiary);
+						Self::deposit_event(Event::KittyAwaitingClaim(kitty_id, beneficiary));
+						return Ok(())
+					}
+					Self::hand_over_locked_kitty(&owner, &beneficiary, kitty_id);
+				},
+				_ => {
+					<LockedKitties<T>>::remove(kitty_id);
+					Self::deposit_event(Event::KittyUnlocked(kitty_id, owner));
+				},
+			}
+			Ok(())
+		}
+
+		/// 领取跨链返回时因为无法质押而等待领取的kitty，只有beneficiary可以领取
+		#[pallet::weight(T::WeightInfo::claim_kitty())]
+		pub fn claim_kitty(origin: OriginFor<T>, kitty_id: T::KittyIndex) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let beneficiary = <UnclaimedKitties<T>>::get(kitty_id).ok_or(<Error<T>>::NothingToClaim)?;
+			ensure!(who == beneficiary, <Error<T>>::NotBeneficiary);
+			let owner = <Owner<T>>::get(kitty_id).ok_or(<Error<T>>::InvalidKittyIndex)?;
+
+			T::Currency::reserve(&who, T::ReservationFee::get())
+				.map_err(|_| <Error<T>>::NoSufficientBalance)?;
+			Self::hand_over_locked_kitty(&owner, &who, kitty_id);
+			Ok(())
+		}
+
+		/// 强制解锁跨链转移的kitty，kitty退回给原拥有者，只能由root调用
+		/// 用于目标平行链的Transact执行失败而不会返回解锁消息的情况，
+		/// 调用前需要确认目标平行链上没有对应的包装kitty
+		#[pallet::weight(T::WeightInfo::force_unlock_kitty())]
+		pub fn force_unlock_kitty(origin: OriginFor<T>, kitty_id: T::KittyIndex) -> DispatchResult {
+			ensure_root(origin)?;
+			ensure!(<LockedKitties<T>>::contains_key(kitty_id), <Error<T>>::KittyNotLocked);
+			let owner = <Owner<T>>::get(kitty_id).ok_or(<Error<T>>::InvalidKittyIndex)?;
+
+			<LockedKitties<T>>::remove(kitty_id);
+			<UnclaimedKitties<T>>::remove(kitty_id);
+
+			Self::deposit_event(Event::KittyUnlocked(kitty_id, owner));
+			Ok(())
+		}
 	}
 
 	impl<T: Config> Pallet<T>
 	{
-		/// 获取随机数
-		fn random_value(sender: &T::AccountId) -> [u8; 16] {
-			let payload = (
-				// 随机数
-				T::Randomness::random_seed(),
-				// 调用该方法的Account
-				&sender,
-				// 这笔交易在block中的Index
-				<frame_system::Pallet<T>>::extrinsic_index(),
+		// 请求延迟铸造kitty，质押ReservationFee并在MintDelay个区块之后揭示dna
+		// 请求时就分配kittyId，parents为繁殖的父母，直接创建的kitty为None
+		fn request_pending_mint(
+			who: T::AccountId,
+			parents: Option<(T::KittyIndex, T::KittyIndex)>,
+		) -> DispatchResult {
+			let reveal_block = <frame_system::Pallet<T>>::block_number().saturating_add(T::MintDelay::get());
+			ensure!(
+				PendingMints::<T>::decode_len(reveal_block).unwrap_or(0) < T::MaxPendingMintsPerBlock::get() as usize,
+				Error::<T>::TooManyPendingMints
+			);
+
+			let kitty_id = Self::next_kitty_id()?;
+
+			T::Currency::reserve(&who, T::ReservationFee::get())
+				.map_err(|_| Error::<T>::NoSufficientBalance)?;
+			PendingMints::<T>::try_mutate(reveal_block, |pending| {
+				pending.try_push(PendingMint { owner: who.clone(), kitty_id, parents })
+			})
+			.map_err(|_| Error::<T>::TooManyPendingMints)?;
+			KittiesCount::<T>::put(kitty_id + One::one());
+
+			Self::deposit_event(Event::MintRequested(who, reveal_block));
+			Ok(())
+		}
+
+		// kittyId，kitties_count()是下一个kitty的id，没有创建过kitty时从0开始
+		fn next_kitty_id() -> Result<T::KittyIndex, DispatchError> {
+			match Self::kitties_count() {
+				Some(id) => {
+					// 如果当前kittyId的id已经超过了KittyIndex的最大值，说明就无法再创建新的Index，就报溢出错误
+					ensure!(id != T::KittyIndex::max_value(), Error::<T>::KittiesCountOverflow);
+					Ok(id)
+				},
+				None => Ok(Zero::zero()),
+			}
+		}
+
+		// 写入一个新的kitty，调用者需要已经为该kitty质押并分配了kittyId
+		fn insert_kitty(
+			who: &T::AccountId,
+			kitty_id: T::KittyIndex,
+			dna: [u8; 16],
+			parents: Option<(T::KittyIndex, T::KittyIndex)>,
+		) {
+			// 更新kitty数据，kitty所有者，kitty的数量以及账户拥有的kittyId列表
+			Kitties::<T>::insert(kitty_id, Some(Kitty(dna)));
+			Owner::<T>::insert(kitty_id, Some(who.clone()));
+			Self::push_kitty_babies_list(who, kitty_id);
+
+			// 记录血统，新出生的kitty可以立即繁殖
+			let generation = parents.map_or(0, |(parent_1, parent_2)| {
+				Self::generation_of(parent_1).max(Self::generation_of(parent_2)).saturating_add(1)
+			});
+			let now = <frame_system::Pallet<T>>::block_number();
+			Genealogy::<T>::insert(
+				kitty_id,
+				KittyGenealogy { parents, generation, birth_block: now, cooldown_until: now },
+			);
+
+			Self::deposit_event(Event::KittyCreate(who.clone(), kitty_id));
+		}
+
+		// 区块n结束时需要揭示的区块，以及全部揭示之后的NextRevealBlock
+		// 除了区块n本身，还从NextRevealBlock开始追赶最多MAX_REVEAL_BLOCKS个被推迟的区块
+		fn reveal_blocks(n: T::BlockNumber) -> (Vec<T::BlockNumber>, T::BlockNumber) {
+			let mut block = NextRevealBlock::<T>::get().unwrap_or(n);
+			let mut blocks = Vec::new();
+			while block < n && blocks.len() < MAX_REVEAL_BLOCKS as usize {
+				blocks.push(block);
+				block = block.saturating_add(One::one());
+			}
+			blocks.push(n);
+			// 被推迟的区块都已经追赶上时，下一个揭示的区块为n + 1
+			let next = if block < n { block } else { n.saturating_add(One::one()) };
+			(blocks, next)
+		}
+
+		// 揭示延迟铸造的dna并铸造kitty，random为揭示时的随机数，block和index为请求所在的揭示区块和序号
+		fn reveal_mint(
+			pending: PendingMint<T::AccountId, T::KittyIndex>,
+			random: &T::Hash,
+			block: T::BlockNumber,
+			index: u32,
+		) {
+			let PendingMint { owner: who, kitty_id, parents } = pending;
+
+			// 繁殖的父母在揭示之前可能已经被移除
+			let parent_dna = match parents.map(|(parent_1, parent_2)| (Self::kitties(parent_1), Self::kitties(parent_2))) {
+				Some((Some(kitty1), Some(kitty2))) => Some((kitty1, kitty2)),
+				Some(_) => return Self::fail_pending_mint(who),
+				None => None,
+			};
+			let seed = (random, block, index, &who).using_encoded(blake2_128);
+
+			// 直接创建的kitty使用随机的dna，繁殖出的kitty由随机数决定从父母继承哪个基因
+			let dna = match parent_dna {
+				Some((kitty1, kitty2)) => crate::dna::inherit(&kitty1.0, &kitty2.0, &seed),
+				None => seed,
+			};
+			Self::insert_kitty(&who, kitty_id, dna, parents);
+
+			if let Some((parent_1, parent_2)) = parents {
+				Self::deposit_event(Event::KittyBred(who, kitty_id, parent_1, parent_2));
+			}
+		}
+
+		// 解锁kitty并转移给beneficiary，调用者需要已经为beneficiary质押
+		fn hand_over_locked_kitty(owner: &T::AccountId, beneficiary: &T::AccountId, kitty_id: T::KittyIndex) {
+			<LockedKitties<T>>::remove(kitty_id);
+			<UnclaimedKitties<T>>::remove(kitty_id);
+			<Owner<T>>::insert(kitty_id, Some(beneficiary.clone()));
+			Self::remove_kittyid_from_kitty_babies_list(owner, kitty_id);
+			Self::push_kitty_babies_list(beneficiary, kitty_id);
+			T::Currency::unreserve(owner, T::ReservationFee::get());
+
+			Self::deposit_event(Event::KittyUnlocked(kitty_id, beneficiary.clone()));
+		}
+
+		// 延迟铸造失败，退回请求时的质押
+		fn fail_pending_mint(who: T::AccountId) {
+			T::Currency::unreserve(&who, T::ReservationFee::get());
+			Self::deposit_event(Event::MintFailed(who));
+		}
+
+		// 检查调用者是否可以使用kitty繁殖，返回kitty的血统信息
+		fn ensure_can_breed(
+			who: &T::AccountId,
+			kitty_id: T::KittyIndex,
+			now: T::BlockNumber,
+		) -> Result<KittyGenealogy<T::KittyIndex, T::BlockNumber>, DispatchError> {
+			ensure!(
+				Owner::<T>::get(kitty_id).as_ref() == Some(who) ||
+					SireRights::<T>::contains_key(kitty_id, who),
+				Error::<T>::NoBreedingRights
+			);
+			ensure!(!LockedKitties::<T>::contains_key(kitty_id), Error::<T>::KittyLocked);
+			let genealogy = Genealogy::<T>::get(kitty_id).unwrap_or_default();
+			ensure!(genealogy.cooldown_until <= now, Error::<T>::KittyOnCooldown);
+			Ok(genealogy)
+		}
+
+		// kitty的代数，没有血统记录的kitty为第0代
+		fn generation_of(kitty_id: T::KittyIndex) -> u32 {
+			Genealogy::<T>::get(kitty_id).map_or(0, |genealogy| genealogy.generation)
+		}
+
+		// 第generation代kitty繁殖后的冷却区块数
+		fn breeding_cooldown(generation: u32) -> T::BlockNumber {
+			T::BreedingCooldown::get().saturating_mul(generation.saturating_add(1).into())
+		}
+
+		// 将kitty从from转移给to，调用者需要已经确认from是kitty的拥有者
+		pub(crate) fn do_transfer(
+			from: T::AccountId,
+			to: T::AccountId,
+			kitty_id: T::KittyIndex,
+		) -> DispatchResult {
+			// 正在拍卖或者跨链锁定的kitty不能转移
+			ensure!(!Auctions::<T>::contains_key(kitty_id), Error::<T>::KittyInAuction);
+			ensure!(!LockedKitties::<T>::contains_key(kitty_id), Error::<T>::KittyLocked);
+
+			// 新的拥有者需要质押一定的金额
+			T::Currency::reserve(&to, T::ReservationFee::get())
+				.map_err(|_| Error::<T>::NoSufficientBalance)?;
+
+			// 插入新的Owner
+			Owner::<T>::insert(kitty_id, Some(to.clone()));
+
+			// 原拥有者的挂单、授予的繁殖权和转移授权随着所有权的转移而失效
+			Self::remove_listing(kitty_id);
+			Self::clear_sire_rights(kitty_id);
+			Self::clear_approval(&from, kitty_id);
+
+			// 更新新的owner之后,退回原拥有者的质押金额
+			T::Currency::unreserve(&from, T::ReservationFee::get());
+
+			// 将kitty_id从old owner的kittyId列表中移除，并更新新owner的kittyId列表
+			Self::remove_kittyid_from_kitty_babies_list(&from, kitty_id);
+			Self::push_kitty_babies_list(&to, kitty_id);
+
+			Self::deposit_event(Event::KittyTransfer(from, to, kitty_id));
+			Ok(())
+		}
+
+		// 设置或者清除kitty的元数据URI
+		fn do_set_metadata_uri(kitty_id: T::KittyIndex, uri: Option<Vec<u8>>) -> DispatchResult {
+			match &uri {
+				Some(uri) => {
+					let bounded: BoundedVec<u8, T::MaxMetadataUriLen> =
+						uri.clone().try_into().map_err(|_| Error::<T>::MetadataUriTooLong)?;
+					KittyMetadataUri::<T>::insert(kitty_id, bounded);
+				},
+				None => KittyMetadataUri::<T>::remove(kitty_id),
+			}
+
+			Self::deposit_event(Event::MetadataUriSet(kitty_id, uri));
+			Ok(())
+		}
+
+		// 销毁kitty，清理kitty的所有存储并退回质押
+		fn burn(owner: &T::AccountId, kitty_id: T::KittyIndex) {
+			// 如果在交易市场的话，首先从交易市场移除
+			Self::remove_listing(kitty_id);
+			<Kitties<T>>::remove(kitty_id);
+			<Owner<T>>::remove(kitty_id);
+			<Genealogy<T>>::remove(kitty_id);
+			<WrappedKitties<T>>::remove(kitty_id);
+			<KittyMetadataUri<T>>::remove(kitty_id);
+			Self::clear_sire_rights(kitty_id);
+			Self::clear_approval(owner, kitty_id);
+			Self::remove_kittyid_from_kitty_babies_list(owner, kitty_id);
+			T::Currency::unreserve(owner, T::ReservationFee::get());
+		}
+
+		// 通过XCM Transact在兄弟平行链上调用本模块的call
+		fn send_kitty_call(para: ParaId, call: Call<T>) -> Result<(), SendError> {
+			let message = Xcm(vec![Transact {
+				origin_type: OriginKind::Native,
+				require_weight_at_most: T::XcmWeightAtMost::get(),
+				call: <T as Config>::Call::from(call).encode().into(),
+			}]);
+			T::XcmSender::send_xcm((1, Junction::Parachain(para.into())), message)
+		}
+
+		// 清除kitty的转移授权，存在授权时通知授权已取消
+		fn clear_approval(owner: &T::AccountId, kitty_id: T::KittyIndex) {
+			if <KittyApprovals<T>>::take(kitty_id).is_some() {
+				Self::deposit_event(Event::Approval(owner.clone(), None, kitty_id));
+			}
+		}
+
+		// operator是否是owner的操作者
+		pub fn is_operator(owner: &T::AccountId, operator: &T::AccountId) -> bool {
+			<KittyOperators<T>>::contains_key(owner, operator)
+		}
+
+		// 清除kitty授予的所有繁殖权
+		fn clear_sire_rights(kitty_id: T::KittyIndex) {
+			let _ = SireRights::<T>::remove_prefix(kitty_id, None);
+		}
+
+		// 查询kitty的家谱，从kitty开始按代向上追溯最多depth代祖先
+		// 同一个祖先只返回一次，已经移除的kitty不会出现在家谱中
+		pub fn query_family_tree(kitty_index: KittyIndex, depth: u32) -> Vec<FamilyTreeNode<T::BlockNumber>> {
+			let depth = depth.min(MAX_FAMILY_TREE_DEPTH);
+			let mut tree: Vec<FamilyTreeNode<T::BlockNumber>> = Vec::new();
+			let mut current: Vec<KittyIndex> = sp_std::vec![kitty_index];
+
+			for level in 0..=depth {
+				let mut next = Vec::new();
+				for index in current {
+					if tree.iter().any(|node| node.kitty_index == index) {
+						continue
+					}
+					let kitty_id: T::KittyIndex = index.saturated_into();
+					if !Kitties::<T>::contains_key(kitty_id) {
+						continue
+					}
+					let genealogy = Genealogy::<T>::get(kitty_id).unwrap_or_default();
+					let parents = genealogy.parents.map(|(parent_1, parent_2)| {
+						(parent_1.saturated_into::<KittyIndex>(), parent_2.saturated_into::<KittyIndex>())
+					});
+					if level < depth {
+						if let Some((parent_1, parent_2)) = parents {
+							next.push(parent_1);
+							next.push(parent_2);
+						}
+					}
+					tree.push(FamilyTreeNode {
+						kitty_index: index,
+						parents,
+						generation: genealogy.generation,
+						birth_block: genealogy.birth_block,
+					});
+				}
+				current = next;
+			}
+			tree
+		}
+
+		// 检查Kitties、Owner、KittyBabies、KittiesCount、KittyMarket以及Auctions之间的一致性
+		#[cfg(any(feature = "try-runtime", test))]
+		pub fn do_try_state() -> Result<(), &'static str> {
+			let next_id = Self::kitties_count().unwrap_or_else(Zero::zero);
+
+			// 每个kitty都有数据、拥有者，并且在拥有者的kittyId列表中
+			let mut kitties = 0usize;
+			for (kitty_id, kitty) in Kitties::<T>::iter() {
+				kitties += 1;
+				ensure!(kitty.is_some(), "kitty without data");
+				ensure!(kitty_id < next_id, "kitty id not below KittiesCount");
+				let owner = Owner::<T>::get(kitty_id).ok_or("kitty without owner")?;
+				ensure!(
+					KittyBabies::<T>::get(&owner).contains(&kitty_id),
+					"kitty missing from owner's KittyBabies"
+				);
+			}
+
+			// 每个拥有者的记录都对应一个kitty
+			let mut owners = 0usize;
+			for (kitty_id, owner) in Owner::<T>::iter() {
+				owners += 1;
+				ensure!(owner.is_some(), "empty owner entry");
+				ensure!(Kitties::<T>::contains_key(kitty_id), "owner entry without kitty");
+			}
+			ensure!(kitties == owners, "Kitties and Owner have different sizes");
+
+			// kittyId列表中的kitty都属于该账户，并且没有重复
+			let mut babies = 0usize;
+			for (who, list) in KittyBabies::<T>::iter() {
+				ensure!(!list.is_empty(), "empty KittyBabies entry");
+				for (index, kitty_id) in list.iter().enumerate() {
+					ensure!(Owner::<T>::get(kitty_id).as_ref() == Some(&who), "KittyBabies entry not owned");
+					ensure!(!list[..index].contains(kitty_id), "duplicate KittyBabies entry");
+				}
+				babies += list.len();
+			}
+			ensure!(kitties == babies, "Kitties and KittyBabies have different sizes");
+
+			// 挂单和拍卖的卖家都是kitty当前的拥有者，并且同一个kitty不能同时挂单和拍卖
+			let mut listings = 0usize;
+			for (kitty_id, listing) in KittyMarket::<T>::iter() {
+				ensure!(Owner::<T>::get(kitty_id) == Some(listing.seller), "listing seller is not owner");
+				ensure!(!Auctions::<T>::contains_key(kitty_id), "kitty both listed and in auction");
+				for sort in MARKET_SORT_ORDERS {
+					let key = Self::market_index_key(sort, kitty_id.saturated_into(), listing.price);
+					ensure!(
+						ListingIndex::<T>::get((sort, key)) == Some((kitty_id, listing.price)),
+						"listing missing from ListingIndex"
+					);
+				}
+				listings += 1;
+			}
+			ensure!(
+				ListingIndex::<T>::iter_keys().count() == listings * MARKET_SORT_ORDERS.len(),
+				"ListingIndex has entries without a listing"
 			);
-			// 使用blake2_128将payload进行hash产生一个128位的数据，作为kitty的DNA（数据）
-			payload.using_encoded(blake2_128)
+			for (kitty_id, auction) in Auctions::<T>::iter() {
+				ensure!(Owner::<T>::get(kitty_id) == Some(auction.seller), "auction seller is not owner");
+			}
+
+			// 血统和繁殖权只属于存在的kitty
+			for kitty_id in Genealogy::<T>::iter_keys() {
+				ensure!(Kitties::<T>::contains_key(kitty_id), "genealogy without kitty");
+			}
+			for (kitty_id, _, _) in SireRights::<T>::iter() {
+				ensure!(Kitties::<T>::contains_key(kitty_id), "sire rights without kitty");
+			}
+
+			// 锁定的kitty不能挂单或者拍卖，包装kitty必须存在
+			for kitty_id in LockedKitties::<T>::iter_keys() {
+				ensure!(Kitties::<T>::contains_key(kitty_id), "locked kitty does not exist");
+				ensure!(!KittyMarket::<T>::contains_key(kitty_id), "locked kitty is listed");
+				ensure!(!Auctions::<T>::contains_key(kitty_id), "locked kitty is in auction");
+			}
+			for kitty_id in UnclaimedKitties::<T>::iter_keys() {
+				ensure!(LockedKitties::<T>::contains_key(kitty_id), "unclaimed kitty is not locked");
+			}
+			for kitty_id in WrappedKitties::<T>::iter_keys() {
+				ensure!(Kitties::<T>::contains_key(kitty_id), "wrapped kitty does not exist");
+			}
+			for kitty_id in KittyMetadataUri::<T>::iter_keys() {
+				ensure!(Kitties::<T>::contains_key(kitty_id), "metadata without kitty");
+			}
+			for kitty_id in KittyApprovals::<T>::iter_keys() {
+				ensure!(Kitties::<T>::contains_key(kitty_id), "approval without kitty");
+			}
+
+			Ok(())
+		}
+
+		// 结算拍卖：买家质押的出价扣除手续费后转给卖家，kitty的质押留在买家账户
+		fn settle_auction(kitty_id: T::KittyIndex) {
+			let auction = match <Auctions<T>>::take(kitty_id) {
+				Some(auction) => auction,
+				None => return,
+			};
+
+			let (winner, price) = match auction.highest_bid {
+				Some(bid) => bid,
+				None => {
+					Self::deposit_event(Event::AuctionExpired(kitty_id));
+					return
+				},
+			};
+
+			let fee = Self::marketplace_fee(price);
+
+			// 出价的质押可能已经被其他模块扣除，卖家或交易市场没有收到全部金额时回滚支付，
+			// 退回最高出价者剩余的质押，kitty仍然属于卖家
+			let paid = with_transaction(|| {
+				match Self::pay_auction(&winner, &auction.seller, price, fee) {
+					Ok(()) => TransactionOutcome::Commit(true),
+					Err(_) => TransactionOutcome::Rollback(false),
+				}
+			});
+			if !paid {
+				T::Currency::unreserve(&winner, price.saturating_add(T::ReservationFee::get()));
+				Self::deposit_event(Event::AuctionFailed(kitty_id, winner));
+				return
+			}
+
+			// 转移kitty的所有权，并退回卖家的kitty质押
+			<Owner<T>>::insert(kitty_id, Some(winner.clone()));
+			Self::clear_sire_rights(kitty_id);
+			Self::clear_approval(&auction.seller, kitty_id);
+			Self::remove_kittyid_from_kitty_babies_list(&auction.seller, kitty_id);
+			Self::push_kitty_babies_list(&winner, kitty_id);
+			T::Currency::unreserve(&auction.seller, T::ReservationFee::get());
+
+			if !fee.is_zero() {
+				Self::deposit_event(Event::MarketplaceFeePaid(kitty_id, fee));
+			}
+			Self::deposit_event(Event::AuctionSettled(kitty_id, auction.seller, winner, price));
+		}
+
+		// 从最高出价者的质押中支付成交价格，扣除手续费后转给卖家
+		// 质押不能转入还不存在的账户，所以手续费先解除质押再转给交易市场的账户
+		// 任何一笔没有全额支付都返回错误，调用方需要回滚
+		fn pay_auction(
+			winner: &T::AccountId,
+			seller: &T::AccountId,
+			price: BalanceOf<T>,
+			fee: BalanceOf<T>,
+		) -> DispatchResult {
+			let unpaid =
+				T::Currency::repatriate_reserved(winner, seller, price - fee, BalanceStatus::Free)?;
+			ensure!(unpaid.is_zero(), Error::<T>::NoSufficientBalance);
+			if !fee.is_zero() {
+				let unpaid = T::Currency::unreserve(winner, fee);
+				ensure!(unpaid.is_zero(), Error::<T>::NoSufficientBalance);
+				T::Currency::transfer(winner, &T::TreasuryAccount::get(), fee, KeepAlive)?;
+			}
+			Ok(())
+		}
+
+		// 成交价格对应的交易市场手续费
+		// 交易市场的账户还不存在时，低于最小余额的手续费无法转入该账户，这时不收取手续费
+		fn marketplace_fee(price: BalanceOf<T>) -> BalanceOf<T> {
+			let fee = T::MarketplaceFee::get() * price;
+			if fee < T::Currency::minimum_balance() &&
+				T::Currency::total_balance(&T::TreasuryAccount::get()).is_zero()
+			{
+				return Zero::zero()
+			}
+			fee
+		}
+
+		// 从交易市场移除挂单，并退回卖家挂单时的质押
+		fn remove_listing(kitty_id: T::KittyIndex) {
+			if let Some(listing) = <KittyMarket<T>>::take(kitty_id) {
+				Self::unindex_listing(kitty_id, listing.price);
+				T::Currency::unreserve(&listing.seller, listing.deposit);
+			}
+		}
+
+		// 将挂单加入每种排序方式的有序索引
+		pub(crate) fn index_listing(kitty_id: T::KittyIndex, price: BalanceOf<T>) {
+			for sort in MARKET_SORT_ORDERS {
+				let key = Self::market_index_key(sort, kitty_id.saturated_into(), price);
+				ListingIndex::<T>::insert((sort, key), (kitty_id, price));
+			}
+		}
+
+		// 从有序索引中移除挂单，price为挂单时的价格
+		fn unindex_listing(kitty_id: T::KittyIndex, price: BalanceOf<T>) {
+			for sort in MARKET_SORT_ORDERS {
+				let key = Self::market_index_key(sort, kitty_id.saturated_into(), price);
+				ListingIndex::<T>::remove((sort, key));
+			}
+		}
+
+		// 挂单在某种排序方式下的排序键：按kittyId排序时为kittyId，按价格排序时为(价格, kittyId)
+		// 都使用大端编码，使key的字节顺序与排序顺序一致，倒序时按位取反
+		fn market_index_key(sort: MarketSortOrder, kitty_id: KittyIndex, price: BalanceOf<T>) -> [u8; 24] {
+			let mut key = [0u8; 24];
+			match sort {
+				MarketSortOrder::IndexAsc | MarketSortOrder::IndexDesc =>
+					key[..8].copy_from_slice(&kitty_id.to_be_bytes()),
+				MarketSortOrder::PriceAsc | MarketSortOrder::PriceDesc => {
+					key[..16].copy_from_slice(&price.saturated_into::<u128>().to_be_bytes());
+					key[16..].copy_from_slice(&kitty_id.to_be_bytes());
+				},
+			}
+			if matches!(sort, MarketSortOrder::IndexDesc | MarketSortOrder::PriceDesc) {
+				key.iter_mut().for_each(|byte| *byte = !*byte);
+			}
+			key
+		}
+
+		// 按排序方式从游标之后读取最多limit个挂单，没有游标时从第一个挂单开始
+		pub(crate) fn market_index_page(
+			sort: MarketSortOrder,
+			cursor: Option<&MarketCursor<BalanceOf<T>>>,
+			limit: usize,
+		) -> Vec<(T::KittyIndex, BalanceOf<T>)> {
+			// 遍历从起始key之后开始，不包括起始key本身
+			let start = match cursor {
+				Some(cursor) => ListingIndex::<T>::hashed_key_for((
+					sort,
+					Self::market_index_key(sort, cursor.kitty_index, cursor.price),
+				)),
+				None => {
+					let mut prefix = ListingIndex::<T>::final_prefix().to_vec();
+					prefix.extend(sort.encode());
+					prefix
+				},
+			};
+			ListingIndex::<T>::iter_from(start)
+				.take_while(|((index_sort, _), _)| *index_sort == sort)
+				.take(limit)
+				.map(|(_, listing)| listing)
+				.collect()
 		}
 
 		// 更新kitty_babies_list
@@ -367,89 +1542,184 @@ pub mod pallet {
 		// 从kitty_babies_list中移除指定的kitty_id
 		fn remove_kittyid_from_kitty_babies_list(who: &T::AccountId, kitty_id: <T as Config>::KittyIndex){
 			let mut babies = KittyBabies::<T>::get(who.clone());
-			// 遍历kitty_babies_list,找到kitty_id的下标，通过下标移除该kitty_id
-			for (index, val) in babies.clone().iter().enumerate(){
-				if val == &kitty_id{
-					babies.swap_remove(index);
-				}
+			babies.retain(|val| val != &kitty_id);
+			// 账户没有kitty时移除整个列表，不留下空的存储项
+			if babies.is_empty() {
+				KittyBabies::<T>::remove(who);
+			} else {
+				KittyBabies::<T>::insert(who, babies);
 			}
-			KittyBabies::<T>::insert(&who.clone(),babies);
 		}
 
-		// 查询当前账户在kittiy_market中的kitty信息(kittyid,price)
+		// 查询交易市场中所有挂单的kitty信息，按kittyId倒序排列
+		// 只遍历挂单的kitty，不会遍历所有创建过的kitty
 		pub fn query_kittiy_market_info() -> GetKittyMarketResult<<T as frame_system::Config>::AccountId, BalanceOf<T>>{
-			log::info!("..............................");
-			log::info!(">>>>>>>>>>>>>>>>>>>>>>> start query kittiy market info <<<<<<<<<<<<<<<<<<<<<<<<<<<");
-			
-			let mut market_info: Vec<KittyInfoById<T::AccountId, BalanceOf<T>>> = [].to_vec();
-			//
-			let mut count = KittiesCount::<T>::get().unwrap();
-			// 将kittyIndex自定义类型转换为u64,得到kitty的数量
-			let numbers = count.saturated_into::<u64>();
-
-			log::info!("kitty数量为：{}",&numbers.clone());
-
-			// 设置一个计数器
-			let mut counter = numbers as i64;
-			//这里按照kittyIndex进行倒序遍历
-			while counter > 0 {
-				// let mut num = KittiesCount::<T>::get().unwrap();
-				counter = counter - 1;
-				log::info!("当前计数为:{}",counter.clone());
-				// 当前这个count就是kitty_id
-				count = count - One::one();
-				// 如果当前遍历的kittyid在market中可以查找到
-				if KittyMarket::<T>::get(count) == Zero::zero(){
-					log::info!("当前kittyid:{}的价格为:{:?},不在挂单市场",count.saturated_into::<u64>(),KittyMarket::<T>::get(count));
-					continue;
-				}else {
-					log::info!("----开始查询market中的kitty信息-----");
-					log::info!("当前kitty id为:{}", count.saturated_into::<u64>());
-					// 查找当前kitty_id的price和owner
-					let price:	BalanceOf<T> = KittyMarket::<T>::get(count);
-					log::info!("当前kitty的价格为:{:?}",price.clone());
-					let owner = Owner::<T>::get(count).unwrap();
-					log::info!("当前kitty的主人为:{:?}",owner.clone());
-					let kitty_dna = Kitties::<T>::get(count).unwrap();
-					log::info!("当前kitty的dna为:{:?}",kitty_dna);
-					// let owner = Owner::<T>::get(kitty_id).unwrap();
-					// Result<Vec<KittyInfoById<AccountId, Balance>>, MarketKittyqueryError>
-					market_info.push(
-						KittyInfoById{
-							kitty_index: count.saturated_into::<u64>(),
-							info: KittyInfo{
-								owner,
-								price,
-								kitty_dna,
-							}
-						}
-					);
-				}
-			}
-			log::info!("当前market_info列表为:{:?}",market_info);
-			log::info!("..............................");
-			log::info!(">>>>>>>>>>>>>>>>>>>>>>> End query kittiy market info <<<<<<<<<<<<<<<<<<<<<<<<<<<");
+			let mut market_info: Vec<KittyInfoById<T::AccountId, BalanceOf<T>>> =
+				KittyMarket::<T>::iter()
+					.filter_map(|(kitty_id, listing)| Self::market_item(kitty_id, listing.price))
+					.collect();
+			market_info.sort_by(|a, b| b.kitty_index.cmp(&a.kitty_index));
 			market_info
+		}
+
+		// 分页查询交易市场，支持按所有者、价格区间过滤以及排序
+		// 每页只从有序索引中读取limit个挂单，过滤之后返回的数量可能少于limit，
+		// 读满limit个挂单时返回最后一个挂单作为游标，调用方继续查询直到游标为None
+		pub fn query_kitty_market(
+			query: MarketQuery<T::AccountId, BalanceOf<T>>,
+		) -> MarketPage<T::AccountId, BalanceOf<T>> {
+			let limit = query.limit.clamp(1, MAX_PAGE_SIZE) as usize;
+
+			let listings = Self::market_index_page(query.sort, query.cursor.as_ref(), limit);
+			let next_cursor = if listings.len() == limit {
+				listings.last().map(|(kitty_id, price)| MarketCursor {
+					price: *price,
+					kitty_index: (*kitty_id).saturated_into::<KittyIndex>(),
+				})
+			} else {
+				None
+			};
 
+			let items = listings
+				.into_iter()
+				.filter(|(_, price)| {
+					query.min_price.map_or(true, |min| *price >= min) &&
+						query.max_price.map_or(true, |max| *price <= max)
+				})
+				.filter_map(|(kitty_id, price)| Self::market_item(kitty_id, price))
+				.filter(|item| query.owner.as_ref().map_or(true, |owner| item.info.owner == *owner))
+				.collect();
+
+			MarketPage { items, next_cursor }
 		}
 
-		// 查询某个账户在market中的kitty信息
-			/* let mut kittyid_list = KittyBabies::get(who.clone());
-			kittyid_list.reverse();  */
-			/* 
-			// 倒序遍历
-			for i in 0..kittyid_list.clone().len(){
-				if let Some(kitty_id) = kittyid_list.clone().get(i){
-					let price =  KittyMarket::get(kitty_id);
-					// Result<Vec<BTreeMap<AccountId, Vec<KittiyMarketInfo<Balance>>>>, MarketKittyqueryError>;
-					let mut kitty_market_info: Vec<KittiyMarketInfo> = [].to_vec();
-					kitty_market_info.push(
-						KittiyMarketInfo{
-							kitty_id,
-							price,
-						}
-					);
-				}
-			} */
+		// 分页查询账户拥有的kitty，按kittyId从小到大排列，cursor为上一页最后一个kittyId
+		pub fn query_owned_kitties(
+			owner: &T::AccountId,
+			cursor: Option<KittyIndex>,
+			limit: u32,
+		) -> OwnedKittiesPage<BalanceOf<T>> {
+			let limit = limit.clamp(1, MAX_PAGE_SIZE) as usize;
+
+			let mut kitty_ids = KittyBabies::<T>::get(owner);
+			kitty_ids.sort();
+
+			let mut items: Vec<OwnedKitty<BalanceOf<T>>> = kitty_ids
+				.into_iter()
+				.map(|kitty_id| (kitty_id, kitty_id.saturated_into::<KittyIndex>()))
+				.filter(|(_, index)| cursor.map_or(true, |cursor| *index > cursor))
+				.filter_map(|(kitty_id, kitty_index)| {
+					Kitties::<T>::get(kitty_id).map(|kitty_dna| OwnedKitty {
+						kitty_index,
+						kitty_dna,
+						price: Self::listing_price(kitty_id),
+					})
+				})
+				.take(limit + 1)
+				.collect();
+
+			let next_cursor = if items.len() > limit {
+				items.truncate(limit);
+				items.last().map(|item| item.kitty_index)
+			} else {
+				None
+			};
+
+			OwnedKittiesPage { items, next_cursor }
+		}
+
+		// 解码kitty的dna，得到外观属性以及稀有度得分
+		pub fn query_kitty_traits(kitty_index: KittyIndex) -> Option<KittyTraits> {
+			Kitties::<T>::get(kitty_index.saturated_into::<T::KittyIndex>())
+				.map(|kitty| crate::dna::decode(&kitty.0))
+		}
+
+		// kitty的挂单价格，没有挂单时为None
+		fn listing_price(kitty_id: T::KittyIndex) -> Option<BalanceOf<T>> {
+			KittyMarket::<T>::get(kitty_id).map(|listing| listing.price)
+		}
+
+		// 组装交易市场中一个kitty的信息，kitty或者所有者不存在时为None
+		fn market_item(
+			kitty_id: T::KittyIndex,
+			price: BalanceOf<T>,
+		) -> Option<KittyInfoById<T::AccountId, BalanceOf<T>>> {
+			let owner = Owner::<T>::get(kitty_id)?;
+			let kitty_dna = Kitties::<T>::get(kitty_id)?;
+			Some(KittyInfoById {
+				kitty_index: kitty_id.saturated_into::<KittyIndex>(),
+				info: KittyInfo { owner, price, kitty_dna },
+			})
+		}
+	}
+
+	// 通用的nonfungibles接口，所有kitty属于同一个class，用()表示
+	impl<T: Config> Inspect<T::AccountId> for Pallet<T> {
+		type InstanceId = T::KittyIndex;
+		type ClassId = ();
+
+		fn owner(_class: &Self::ClassId, instance: &Self::InstanceId) -> Option<T::AccountId> {
+			Owner::<T>::get(instance)
+		}
+
+		// 元数据URI通过key为METADATA_URI_KEY的属性读取
+		fn attribute(_class: &Self::ClassId, instance: &Self::InstanceId, key: &[u8]) -> Option<Vec<u8>> {
+			if key == METADATA_URI_KEY {
+				KittyMetadataUri::<T>::get(instance).map(|uri| uri.into_inner())
+			} else {
+				None
+			}
+		}
+
+		fn can_transfer(_class: &Self::ClassId, instance: &Self::InstanceId) -> bool {
+			Kitties::<T>::contains_key(instance) &&
+				!Auctions::<T>::contains_key(instance) &&
+				!LockedKitties::<T>::contains_key(instance)
+		}
+	}
+
+	impl<T: Config> Transfer<T::AccountId> for Pallet<T> {
+		fn transfer(
+			_class: &Self::ClassId,
+			instance: &Self::InstanceId,
+			destination: &T::AccountId,
+		) -> DispatchResult {
+			let owner = Owner::<T>::get(instance).ok_or(Error::<T>::InvalidKittyIndex)?;
+			Self::do_transfer(owner, destination.clone(), *instance)
+		}
+	}
+
+	impl<T: Config> Mutate<T::AccountId> for Pallet<T> {
+		// 和create一样发起延迟铸造，instance必须是下一个kittyId
+		// kitty在MintDelay个区块之后揭示dna时才真正存在，揭示失败时退回质押
+		fn mint_into(_class: &Self::ClassId, instance: &Self::InstanceId, who: &T::AccountId) -> DispatchResult {
+			ensure!(*instance == Self::next_kitty_id()?, Error::<T>::InvalidKittyIndex);
+			Self::request_pending_mint(who.clone(), None)
+		}
+
+		fn burn_from(_class: &Self::ClassId, instance: &Self::InstanceId) -> DispatchResult {
+			let owner = Owner::<T>::get(instance).ok_or(Error::<T>::InvalidKittyIndex)?;
+			ensure!(!Auctions::<T>::contains_key(instance), Error::<T>::KittyInAuction);
+			ensure!(!LockedKitties::<T>::contains_key(instance), Error::<T>::KittyLocked);
+
+			Self::burn(&owner, *instance);
+
+			Self::deposit_event(Event::RemovedKitty(owner, *instance));
+			Ok(())
+		}
+
+		// 只支持设置元数据URI
+		fn set_attribute(
+			_class: &Self::ClassId,
+			instance: &Self::InstanceId,
+			key: &[u8],
+			value: &[u8],
+		) -> DispatchResult {
+			ensure!(key == METADATA_URI_KEY, TokenError::Unsupported);
+			ensure!(Kitties::<T>::contains_key(instance), Error::<T>::InvalidKittyIndex);
+			// 和set_metadata_uri一样，跨链锁定的kitty不能修改元数据URI
+			ensure!(!LockedKitties::<T>::contains_key(instance), Error::<T>::KittyLocked);
+			Self::do_set_metadata_uri(*instance, Some(value.to_vec()))
+		}
 	}
 }
@@ -0,0 +1,48 @@
+// kitty模块的存储迁移
+
+pub mod v1 {
+	use crate::{BalanceOf, Config, KittyMarket, Listing, Owner, Pallet};
+	use frame_support::{
+		traits::{Get, GetStorageVersion, StorageVersion},
+		weights::Weight,
+	};
+	use sp_runtime::traits::Zero;
+
+	/// 版本0 -> 版本1：KittyMarket的值从挂单价格改为Listing
+	///
+	/// 旧的挂单没有质押，迁移后的deposit为0；价格为0或者kitty已经没有所有者的挂单会被移除
+	/// 保留下来的挂单同时写入交易市场的有序索引ListingIndex
+	pub fn migrate<T: Config>() -> Weight {
+		let on_chain_version = Pallet::<T>::on_chain_storage_version();
+		if on_chain_version >= 1 {
+			log::info!(
+				target: "runtime::kitties",
+				"skipping v1 migration: on-chain storage version is {:?}",
+				on_chain_version,
+			);
+			return T::DbWeight::get().reads(1)
+		}
+
+		let mut count = 0u64;
+		KittyMarket::<T>::translate::<BalanceOf<T>, _>(|kitty_id, price| {
+			count += 1;
+			if price.is_zero() {
+				return None
+			}
+			Owner::<T>::get(kitty_id)
+				.map(|seller| Listing { seller, price, deposit: Zero::zero() })
+		});
+		let mut indexed = 0u64;
+		for (kitty_id, listing) in KittyMarket::<T>::iter() {
+			indexed += 1;
+			Pallet::<T>::index_listing(kitty_id, listing.price);
+		}
+
+		StorageVersion::new(1).put::<Pallet<T>>();
+		log::info!(target: "runtime::kitties", "v1 migration translated {} listings", count);
+
+		// 每个挂单读取一次KittyMarket和Owner、写入一次KittyMarket，保留的挂单再读取一次并写入四条索引，
+		// 最后读写一次存储版本
+		T::DbWeight::get().reads_writes(count * 2 + indexed + 1, count + indexed * 4 + 1)
+	}
+}
@@ -1,11 +1,14 @@
 use crate as pallet_kitties;
-use frame_support::parameter_types;
+use frame_support::{parameter_types, traits::{Get, Randomness}};
 use frame_system as system;
 use sp_core::H256;
 use sp_runtime::{
 	testing::Header,
 	traits::{BlakeTwo256, IdentityLookup},
+	Permill,
 };
+use std::cell::RefCell;
+use xcm::latest::prelude::*;
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
 type Block = frame_system::mocking::MockBlock<Test>;
 
@@ -25,6 +28,7 @@ frame_support::construct_runtime!(
 		KittiesModule: pallet_kitties::{Pallet, Call, Storage, Event<T>},
 		RandomnessCollectiveFlip: pallet_randomness_collective_flip::{Pallet, Storage},
 		Balances: pallet_balances::{Pallet, Call, Storage, Event<T>, Config<T>},
+		CumulusXcm: cumulus_pallet_xcm::{Pallet, Event<T>, Origin},
 	}
 );
 
@@ -35,7 +39,7 @@ parameter_types! {
 
 impl system::Config for Test {
 	// 对于未使用的关联类型给的是一个空的tuple
-	type BaseCallFilter = ();
+	type BaseCallFilter = frame_support::traits::Everything;
 	type BlockWeights = ();
 	type BlockLength = ();
 	type DbWeight = ();
@@ -58,14 +62,30 @@ impl system::Config for Test {
 	type SystemWeightInfo = ();
 	type SS58Prefix = SS58Prefix;
 	type OnSetCode = ();
+	type MaxConsumers = frame_support::traits::ConstU32<16>;
 }
 
 parameter_types! {
-	// 这里需要设置为1,
-	pub const ExistentialDeposit: u128 = 1;
 	pub const MaxLocks: u32 = 50;
 }
 
+thread_local! {
+	// 账户的最小余额，默认为1，可以在测试中修改为更接近实际的值
+	pub static EXISTENTIAL_DEPOSIT: RefCell<Balance> = RefCell::new(1);
+}
+
+pub struct ExistentialDeposit;
+impl Get<Balance> for ExistentialDeposit {
+	fn get() -> Balance {
+		EXISTENTIAL_DEPOSIT.with(|v| *v.borrow())
+	}
+}
+
+/// 设置账户的最小余额
+pub fn set_existential_deposit(value: Balance) {
+	EXISTENTIAL_DEPOSIT.with(|v| *v.borrow_mut() = value);
+}
+
 impl pallet_balances::Config for Test {
 	type MaxLocks = MaxLocks;
 	type MaxReserves = ();
@@ -82,18 +102,104 @@ impl pallet_balances::Config for Test {
 
 impl pallet_randomness_collective_flip::Config for Test {}
 
+thread_local! {
+	// 为false时模拟当前区块没有可用的随机数
+	pub static RANDOMNESS_AVAILABLE: RefCell<bool> = RefCell::new(true);
+}
+
+/// 测试用的随机数，可以模拟没有随机数的区块
+pub struct TestRandomness;
+impl Randomness<Option<H256>, u64> for TestRandomness {
+	fn random(subject: &[u8]) -> (Option<H256>, u64) {
+		let (random, block_number) = RandomnessCollectiveFlip::random(subject);
+		(RANDOMNESS_AVAILABLE.with(|v| *v.borrow()).then(|| random), block_number)
+	}
+}
+
+/// 设置当前是否有可用的随机数
+pub fn set_randomness_available(available: bool) {
+	RANDOMNESS_AVAILABLE.with(|v| *v.borrow_mut() = available);
+}
+
 parameter_types! {
 	// 设置需要质押的金额，可以在runtime里动态调整的，这里设置为4，即创建一个kitty需要质押4个代币
     pub const ReservationFee: Balance = 4;
+	// 挂单时需要质押的金额
+	pub const ListingDeposit: Balance = 2;
+	// 交易市场收取成交价格的10%作为手续费
+	pub const MarketplaceFee: Permill = Permill::from_percent(10);
+	// 收取交易市场手续费的账户
+	pub const TreasuryAccount: u64 = 99;
+	// 同一个区块最多结束2个拍卖
+	pub const MaxAuctionsPerBlock: u32 = 2;
+	// 第0代kitty繁殖后需要等待5个区块
+	pub const BreedingCooldown: u64 = 5;
+	// 延迟铸造在请求的3个区块之后揭示dna
+	pub const MintDelay: u64 = 3;
+	pub const MaxPendingMintsPerBlock: u32 = 2;
+	// 跨链转移kitty时Transact最多可以使用的weight
+	pub const XcmWeightAtMost: u64 = 1_000;
+	// 跨链转移kitty的目标兄弟平行链
+	pub KittyDestination: cumulus_primitives_core::ParaId = 4000.into();
+	pub const MaxMetadataUriLen: u32 = 16;
 }
 
 impl pallet_kitties::Config for Test {
 	type Event = Event;
-	type Randomness = RandomnessCollectiveFlip;
+	type Randomness = TestRandomness;
 	// 代币的类型使用上面引入的Balances,其类型为pallet_balances
 	type Currency = Balances;
 	type KittyIndex = u64;
 	type ReservationFee = ReservationFee;
+	type ListingDeposit = ListingDeposit;
+	type MarketplaceFee = MarketplaceFee;
+	type TreasuryAccount = TreasuryAccount;
+	type WeightInfo = ();
+	type MaxAuctionsPerBlock = MaxAuctionsPerBlock;
+	type BreedingCooldown = BreedingCooldown;
+	type MintDelay = MintDelay;
+	type MaxPendingMintsPerBlock = MaxPendingMintsPerBlock;
+	type Origin = Origin;
+	type Call = Call;
+	type XcmSender = TestSendXcm;
+	type XcmWeightAtMost = XcmWeightAtMost;
+	type KittyDestination = KittyDestination;
+	type MaxMetadataUriLen = MaxMetadataUriLen;
+}
+
+impl cumulus_pallet_xcm::Config for Test {
+	type Event = Event;
+	type XcmExecutor = ();
+}
+
+thread_local! {
+	// 测试中发送的跨链消息：(目标位置, 消息)
+	pub static SENT_XCM: RefCell<Vec<(MultiLocation, Xcm<()>)>> = RefCell::new(Vec::new());
+	// 为true时跨链消息发送失败
+	pub static XCM_SEND_FAILS: RefCell<bool> = RefCell::new(false);
+}
+
+/// 取出测试中发送的所有跨链消息
+pub fn sent_xcm() -> Vec<(MultiLocation, Xcm<()>)> {
+	SENT_XCM.with(|q| q.borrow_mut().drain(..).collect())
+}
+
+/// 设置之后的跨链消息是否发送失败
+pub fn set_xcm_send_fails(fails: bool) {
+	XCM_SEND_FAILS.with(|f| *f.borrow_mut() = fails);
+}
+
+/// 测试用的跨链消息发送模块，只记录消息不真正发送
+pub struct TestSendXcm;
+impl SendXcm for TestSendXcm {
+	fn send_xcm(dest: impl Into<MultiLocation>, msg: Xcm<()>) -> SendResult {
+		let dest = dest.into();
+		if XCM_SEND_FAILS.with(|f| *f.borrow()) {
+			return Err(SendError::CannotReachDestination(dest, msg))
+		}
+		SENT_XCM.with(|q| q.borrow_mut().push((dest, msg)));
+		Ok(())
+	}
 }
 
 // Build genesis storage according to the mock runtime.
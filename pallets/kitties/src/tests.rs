@@ -1,13 +1,40 @@
-use crate::{mock::*};
-use frame_support::{assert_noop, assert_ok};
+use crate::mock::{Call, Event, *};
+use frame_support::{assert_noop, assert_ok, traits::{tokens::nonfungibles, Currency, Get, Hooks, ReservableCurrency}};
+use sp_runtime::TokenError;
+use codec::Encode;
+use xcm::latest::{Instruction::Transact, Junction, MultiLocation, OriginKind, Xcm};
 use super::*;
 
+/// 在不推进区块的情况下，揭示当前区块请求的延迟铸造
+fn reveal_pending_mints(){
+	KittiesModule::on_finalize(System::block_number() + MintDelay::get());
+}
+
+/// 创建kitty并立即揭示，返回时kitty已经铸造
+fn create_kitty(who: u64){
+	assert_ok!(KittiesModule::create(Origin::signed(who)));
+	reveal_pending_mints();
+}
+
+/// 繁殖kitty并立即揭示，返回时child kitty已经铸造
+fn breed_kitty(who: u64, kitty_id_1: u64, kitty_id_2: u64){
+	assert_ok!(KittiesModule::breed(Origin::signed(who),kitty_id_1,kitty_id_2));
+	reveal_pending_mints();
+}
+
 /// 创建Kitty的测试用例
 #[test]
 fn create_kitties_work(){
 	new_test_ext().execute_with(|| {
 		// 账户0创建kitty，此时的kitty的id为0,其中需要质押4个代币
+		// 创建时只质押，dna在MintDelay个区块之后揭示
 		assert_ok!(KittiesModule::create(Origin::signed(0)));
+		System::assert_last_event(Event::KittiesModule(crate::Event::MintRequested(0,4)));
+		// 请求时就分配了kittyId
+		assert_eq!(KittiesCount::<Test>::get(),Some(1));
+		assert!(!Kitties::<Test>::contains_key(0));
+		assert_eq!(PendingMints::<Test>::get(4).into_inner(),vec![PendingMint{ owner: 0, kitty_id: 0, parents: None }]);
+		reveal_pending_mints();
 		// 判断是否余额为96
 		assert_eq!(Balances::free_balance(0),196);
 		// 判断id为0的kitty的拥有者是否为账户0
@@ -21,12 +48,38 @@ fn create_kitties_work(){
 
 
 
+/// 延迟铸造的测试
+#[test]
+fn request_mint_work(){
+	new_test_ext().execute_with(|| {
+		// 请求时只质押，不铸造kitty
+		assert_ok!(KittiesModule::request_mint(Origin::signed(0)));
+		System::assert_last_event(Event::KittiesModule(crate::Event::MintRequested(0,4)));
+		assert_eq!(Balances::reserved_balance(0),4);
+		assert_eq!(KittiesCount::<Test>::get(),Some(1));
+		assert!(!Owner::<Test>::contains_key(0));
+		assert_ok!(KittiesModule::request_mint(Origin::signed(1)));
+		// 揭示区块的请求数量已满
+		assert_noop!(KittiesModule::request_mint(Origin::signed(2)),Error::<Test>::TooManyPendingMints);
+
+		// 在揭示区块结束时铸造kitty
+		System::set_block_number(4);
+		KittiesModule::on_finalize(4);
+		assert_eq!(Owner::<Test>::get(0),Some(0));
+		assert_eq!(Owner::<Test>::get(1),Some(1));
+		assert_eq!(KittyBabies::<Test>::get(1),vec![1]);
+		assert!(!PendingMints::<Test>::contains_key(4));
+		assert_eq!(Genealogy::<Test>::get(0).map(|genealogy| genealogy.birth_block),Some(4));
+		assert_ok!(KittiesModule::do_try_state());
+	})
+}
+
 /// 转移kitty的测试
 #[test]
 fn transfer_kitty_work(){
 	new_test_ext().execute_with(|| {
 		// 创建Kitty
-		assert_ok!(KittiesModule::create(Origin::signed(0)));
+		create_kitty(0);
 		// 判断创建kitty后，判断账户0的kittyId列表中的kittyId是否为0
 		assert_eq!(KittyBabies::<Test>::get(0)[0],0);
 		// 将id为0的kitty从账户0转移给账户1
@@ -49,11 +102,11 @@ fn breed_kitty_work(){
 	new_test_ext().execute_with(|| {
 		// 用账户0创建两个kitty
 		// 创建id为0的kitty
-		assert_ok!(KittiesModule::create(Origin::signed(0)));
+		create_kitty(0);
 		// 创建id为1的kitty
-		assert_ok!(KittiesModule::create(Origin::signed(0)));
+		create_kitty(0);
 		// 账户0将两个kitty进行繁殖出id为2的子孙kitty
-		assert_ok!(KittiesModule::breed(Origin::signed(0),0,1));
+		breed_kitty(0,0,1);
 		// 查看是否已经繁殖了id为2的kitty，并且其主人为账户0
 		assert_eq!(Owner::<Test>::get(2),Some(0));
 		// 判断账户0的kittyId列表有三个kittyId
@@ -61,10 +114,182 @@ fn breed_kitty_work(){
 
 		// 判断列表的最后一个id是否为2
 		assert_eq!(KittyBabies::<Test>::get(0)[2],2);
+		// 繁殖出的kitty也需要质押
+		assert_eq!(Balances::reserved_balance(0),12);
 		// 创建id为3的kitty
-		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		create_kitty(1);
 		// 检测是否总共的kitty数量为4
 		assert_eq!(KittiesCount::<Test>::get().unwrap(),4);
+		assert_ok!(KittiesModule::do_try_state());
+	})
+}
+
+/// 繁殖的dna在揭示区块由父母的dna和当时的随机数决定
+#[test]
+fn breed_reveal_work(){
+	new_test_ext().execute_with(|| {
+		create_kitty(0);
+		create_kitty(0);
+		// 繁殖时父母立即进入冷却，child kitty延迟铸造
+		assert_ok!(KittiesModule::breed(Origin::signed(0),0,1));
+		System::assert_last_event(Event::KittiesModule(crate::Event::MintRequested(0,4)));
+		assert_eq!(PendingMints::<Test>::get(4).into_inner(),vec![PendingMint{ owner: 0, kitty_id: 2, parents: Some((0,1)) }]);
+		assert_eq!(KittiesCount::<Test>::get(),Some(3));
+		assert_eq!(Balances::reserved_balance(0),12);
+		assert_noop!(KittiesModule::breed(Origin::signed(0),0,1),Error::<Test>::KittyOnCooldown);
+
+		reveal_pending_mints();
+		System::assert_last_event(Event::KittiesModule(crate::Event::KittyBred(0,2,0,1)));
+		assert_eq!(Owner::<Test>::get(2),Some(0));
+		assert_eq!(Genealogy::<Test>::get(2).and_then(|genealogy| genealogy.parents),Some((0,1)));
+		assert!(!PendingMints::<Test>::contains_key(4));
+		assert_ok!(KittiesModule::do_try_state());
+	})
+}
+
+/// 没有随机数的区块不揭示延迟铸造，之后有随机数的区块继续揭示
+#[test]
+fn reveal_deferred_without_randomness_work(){
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesModule::create(Origin::signed(0)));
+		set_randomness_available(false);
+		System::set_block_number(4);
+		KittiesModule::on_finalize(4);
+		assert!(!Kitties::<Test>::contains_key(0));
+		assert!(PendingMints::<Test>::contains_key(4));
+		assert_eq!(NextRevealBlock::<Test>::get(),Some(4));
+
+		set_randomness_available(true);
+		System::set_block_number(5);
+		KittiesModule::on_finalize(5);
+		assert_eq!(Owner::<Test>::get(0),Some(0));
+		assert!(!PendingMints::<Test>::contains_key(4));
+		assert_eq!(NextRevealBlock::<Test>::get(),Some(6));
+
+		// 除了当前区块，每个区块最多追赶4个被推迟的区块
+		NextRevealBlock::<Test>::put(10);
+		System::set_block_number(20);
+		KittiesModule::on_finalize(20);
+		assert_eq!(NextRevealBlock::<Test>::get(),Some(14));
+	})
+}
+
+/// 父母在揭示之前被移除时，繁殖失败并退回质押
+#[test]
+fn breed_reveal_without_parent_failed(){
+	new_test_ext().execute_with(|| {
+		create_kitty(0);
+		create_kitty(0);
+		assert_ok!(KittiesModule::breed(Origin::signed(0),0,1));
+		assert_ok!(KittiesModule::remove_kitty(Origin::signed(0),1));
+		assert_eq!(Balances::reserved_balance(0),8);
+
+		reveal_pending_mints();
+		System::assert_last_event(Event::KittiesModule(crate::Event::MintFailed(0)));
+		// kittyId 2在请求时已经分配，揭示失败后不再使用
+		assert_eq!(KittiesCount::<Test>::get(),Some(3));
+		assert!(!Kitties::<Test>::contains_key(2));
+		assert_eq!(Balances::reserved_balance(0),4);
+		assert_ok!(KittiesModule::do_try_state());
+	})
+}
+
+/// 余额不足以质押时不能繁殖kitty
+#[test]
+fn breed_kitty_without_deposit_failed(){
+	new_test_ext().execute_with(|| {
+		create_kitty(0);
+		create_kitty(0);
+		// 转走账户0的大部分余额，只剩下不足质押的金额
+		assert_ok!(Balances::transfer(Origin::signed(0),1,190));
+		assert_noop!(KittiesModule::breed(Origin::signed(0),0,1),Error::<Test>::NoSufficientBalance);
+		assert_eq!(KittiesCount::<Test>::get().unwrap(),2);
+	})
+}
+
+
+/// 繁殖记录血统并进入冷却
+#[test]
+fn breed_genealogy_work(){
+	new_test_ext().execute_with(|| {
+		create_kitty(0);
+		create_kitty(0);
+		assert_eq!(
+			Genealogy::<Test>::get(0),
+			Some(KittyGenealogy{ parents: None, generation: 0, birth_block: 1, cooldown_until: 1 })
+		);
+
+		System::set_block_number(2);
+		breed_kitty(0,0,1);
+		System::assert_last_event(Event::KittiesModule(crate::Event::KittyBred(0,2,0,1)));
+		assert_eq!(
+			Genealogy::<Test>::get(2),
+			Some(KittyGenealogy{ parents: Some((0,1)), generation: 1, birth_block: 2, cooldown_until: 2 })
+		);
+		// 第0代的父母冷却5个区块
+		assert_eq!(Genealogy::<Test>::get(0).map(|genealogy| genealogy.cooldown_until),Some(7));
+		assert_noop!(KittiesModule::breed(Origin::signed(0),0,2),Error::<Test>::KittyOnCooldown);
+
+		// 第1代的kitty繁殖后冷却10个区块
+		create_kitty(0);
+		breed_kitty(0,2,3);
+		assert_eq!(Genealogy::<Test>::get(2).map(|genealogy| genealogy.cooldown_until),Some(12));
+		assert_eq!(Genealogy::<Test>::get(4).map(|genealogy| genealogy.generation),Some(2));
+
+		System::set_block_number(7);
+		breed_kitty(0,0,1);
+		assert_ok!(KittiesModule::do_try_state());
+	})
+}
+
+/// 只能使用自己的kitty或者被授予繁殖权的kitty繁殖
+#[test]
+fn sire_rights_work(){
+	new_test_ext().execute_with(|| {
+		create_kitty(0);
+		create_kitty(1);
+		assert_noop!(KittiesModule::breed(Origin::signed(0),0,1),Error::<Test>::NoBreedingRights);
+		// 只有拥有者可以授予繁殖权
+		assert_noop!(KittiesModule::grant_sire_rights(Origin::signed(0),1,0),Error::<Test>::NotOwner);
+		assert_ok!(KittiesModule::grant_sire_rights(Origin::signed(1),1,0));
+		System::assert_last_event(Event::KittiesModule(crate::Event::SireRightsGranted(1,0)));
+		// 繁殖出的kitty属于调用者
+		breed_kitty(0,0,1);
+		assert_eq!(Owner::<Test>::get(2),Some(0));
+
+		// 撤销繁殖权
+		assert_ok!(KittiesModule::revoke_sire_rights(Origin::signed(1),1,0));
+		assert!(!SireRights::<Test>::contains_key(1,0));
+
+		// 转移kitty后授予的繁殖权失效
+		assert_ok!(KittiesModule::grant_sire_rights(Origin::signed(1),1,2));
+		assert_ok!(KittiesModule::transfer(Origin::signed(1),0,1));
+		assert!(!SireRights::<Test>::contains_key(1,2));
+	})
+}
+
+/// 查询kitty的家谱
+#[test]
+fn query_family_tree_work(){
+	new_test_ext().execute_with(|| {
+		for _ in 0..3 {
+			create_kitty(0);
+		}
+		// kitty 3的父母为0和1，kitty 4的父母为3和2
+		breed_kitty(0,0,1);
+		breed_kitty(0,3,2);
+
+		let ids = |depth| -> Vec<u64> {
+			KittiesModule::query_family_tree(4, depth).iter().map(|node| node.kitty_index).collect()
+		};
+		assert_eq!(ids(0), vec![4]);
+		assert_eq!(ids(1), vec![4, 3, 2]);
+		assert_eq!(ids(2), vec![4, 3, 2, 0, 1]);
+		let tree = KittiesModule::query_family_tree(4, 2);
+		assert_eq!(tree[0].parents, Some((3, 2)));
+		assert_eq!(tree[0].generation, 2);
+		// 不存在的kitty没有家谱
+		assert!(KittiesModule::query_family_tree(10, 2).is_empty());
 	})
 }
 
@@ -74,13 +299,18 @@ fn breed_kitty_work(){
 fn sell_kitty_work(){
 	new_test_ext().execute_with(|| {
 		// 账户0创建了一个id为0的kitty
-		assert_ok!(KittiesModule::create(Origin::signed(0)));
+		create_kitty(0);
 		// 账户1创建了一个id为1的kitty
-		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		create_kitty(1);
 		// 账户0将id为0的kitty放到kitty市场进行挂单,标价20个代币
 		assert_ok!(KittiesModule::sell_kitties(Origin::signed(0),0,20));
-		// 查看kitty市场，是否id为0的kitty的挂单价格为20
-		assert_eq!(KittyMarket::<Test>::get(0),20);
+		// 查看kitty市场，是否id为0的kitty的挂单价格为20，并质押了2个代币
+		assert_eq!(KittyMarket::<Test>::get(0),Some(Listing{ seller: 0, price: 20, deposit: 2 }));
+		assert_eq!(Balances::reserved_balance(0),6);
+		// 修改挂单价格时不会重复质押
+		assert_ok!(KittiesModule::sell_kitties(Origin::signed(0),0,25));
+		assert_eq!(KittyMarket::<Test>::get(0).map(|listing| listing.price),Some(25));
+		assert_eq!(Balances::reserved_balance(0),6);
 		// 账户1试图将id为0的kitty放到市场进行挂单，就会出现错误，因为它不是该kitty的拥有者
 		assert_noop!(KittiesModule::sell_kitties(Origin::signed(1),0,30),Error::<Test>::NotOwner);
 	})
@@ -92,20 +322,948 @@ fn sell_kitty_work(){
 fn buy_kitty_work(){
 	new_test_ext().execute_with(|| {
 		// 账户0创建了一个id为0的kitty
-		assert_ok!(KittiesModule::create(Origin::signed(0)));
+		create_kitty(0);
 		// 账户0将id为0的kitty放到kitty市场进行挂单,标价20个代币
 		assert_ok!(KittiesModule::sell_kitties(Origin::signed(0),0,20));
 		// 使用账户1购买id为0的kitty
-		assert_ok!(KittiesModule::buy_kitties(Origin::signed(1),0));
+		assert_ok!(KittiesModule::buy_kitties(Origin::signed(1),0,20));
 		// 判断是否账户1为id为0的kitty的主人
 		assert_eq!(Owner::<Test>::get(0),Some(1));
 		// 判断账户0的kittyId列表是否为空
 		assert_eq!(KittyBabies::<Test>::get(0).len(),0);
 		// 判断账户1的kittyId列表中的kittyId是否为0
 		assert_eq!(KittyBabies::<Test>::get(1)[0],0);
+		// 买家支付20个代币，其中10%的手续费转给交易市场的账户，卖家收到18个代币
+		// 卖家的kitty质押和挂单质押都被退回
+		assert_eq!(Balances::free_balance(0),218);
+		assert_eq!(Balances::reserved_balance(0),0);
+		assert_eq!(Balances::free_balance(1),276);
+		assert_eq!(Balances::reserved_balance(1),4);
+		assert_eq!(Balances::free_balance(TreasuryAccount::get()),2);
 		// 判断是否市场上还有id为0的kitty,即是否还可以购买kitty 0，如果还可以购买就报错
-		assert_noop!(KittiesModule::buy_kitties(Origin::signed(2),0),Error::<Test>::InvalidKittyIndex);
+		assert_noop!(KittiesModule::buy_kitties(Origin::signed(2),0,20),Error::<Test>::NotForSale);
+	})
+}
+
+/// 交易市场的账户不存在时，低于最小余额的手续费不收取，成交不会失败
+#[test]
+fn buy_kitty_fee_below_existential_deposit_work(){
+	new_test_ext().execute_with(|| {
+		set_existential_deposit(10);
+		create_kitty(0);
+		assert_ok!(KittiesModule::sell_kitties(Origin::signed(0),0,50));
+
+		// 手续费为5，低于最小余额10，卖家收到全部价格，不产生手续费事件
+		assert_ok!(KittiesModule::buy_kitties(Origin::signed(1),0,50));
+		assert!(!System::events().iter().any(|record| matches!(
+			record.event,
+			Event::KittiesModule(crate::Event::MarketplaceFeePaid(..))
+		)));
+		assert_eq!(Owner::<Test>::get(0),Some(1));
+		assert_eq!(Balances::free_balance(0),250);
+		assert_eq!(Balances::total_balance(&TreasuryAccount::get()),0);
+
+		// 交易市场的账户存在之后，正常收取手续费
+		let _ = Balances::deposit_creating(&TreasuryAccount::get(),10);
+		assert_ok!(KittiesModule::sell_kitties(Origin::signed(1),0,50));
+		assert_ok!(KittiesModule::buy_kitties(Origin::signed(2),0,50));
+		System::assert_has_event(Event::KittiesModule(crate::Event::MarketplaceFeePaid(0,5)));
+		assert_eq!(Balances::free_balance(TreasuryAccount::get()),15);
+	})
+}
+
+/// 买kitty失败的测试
+#[test]
+fn buy_kitty_failed(){
+	new_test_ext().execute_with(|| {
+		create_kitty(0);
+		// 没有挂单的kitty不能购买
+		assert_noop!(KittiesModule::buy_kitties(Origin::signed(1),0,20),Error::<Test>::NotForSale);
+		assert_ok!(KittiesModule::sell_kitties(Origin::signed(0),0,20));
+		// 不能购买自己挂单的kitty
+		assert_noop!(KittiesModule::buy_kitties(Origin::signed(0),0,20),Error::<Test>::BuyOwnKitty);
+		// 挂单价格高于买家愿意支付的最高价格
+		assert_noop!(KittiesModule::buy_kitties(Origin::signed(1),0,19),Error::<Test>::PriceTooHigh);
+	})
+}
+
+/// 取消挂单的测试
+#[test]
+fn cancel_listing_work(){
+	new_test_ext().execute_with(|| {
+		create_kitty(0);
+		assert_ok!(KittiesModule::sell_kitties(Origin::signed(0),0,20));
+		// 只有卖家可以取消挂单
+		assert_noop!(KittiesModule::cancel_listing(Origin::signed(1),0),Error::<Test>::NotOwner);
+		assert_ok!(KittiesModule::cancel_listing(Origin::signed(0),0));
+		// 挂单被移除，挂单质押被退回
+		assert!(!KittyMarket::<Test>::contains_key(0));
+		assert_eq!(Balances::reserved_balance(0),4);
+		System::assert_last_event(Event::KittiesModule(crate::Event::ListingCancelled(0)));
+		// 已经取消的挂单不能再次取消
+		assert_noop!(KittiesModule::cancel_listing(Origin::signed(0),0),Error::<Test>::NotForSale);
+	})
+}
+
+/// 转移kitty时挂单失效
+#[test]
+fn transfer_invalidates_listing_work(){
+	new_test_ext().execute_with(|| {
+		create_kitty(0);
+		assert_ok!(KittiesModule::sell_kitties(Origin::signed(0),0,20));
+		assert_ok!(KittiesModule::transfer(Origin::signed(0),1,0));
+		// 挂单被移除，原拥有者的挂单质押被退回
+		assert!(!KittyMarket::<Test>::contains_key(0));
+		assert_eq!(Balances::reserved_balance(0),0);
+		assert_noop!(KittiesModule::buy_kitties(Origin::signed(2),0,20),Error::<Test>::NotForSale);
 	})
 }
 
 /// 移除kitty测试
+#[test]
+fn remove_kitty_work(){
+	new_test_ext().execute_with(|| {
+		// 账户0创建了一个id为0的kitty，并挂单
+		create_kitty(0);
+		assert_ok!(KittiesModule::sell_kitties(Origin::signed(0),0,20));
+		// 移除kitty后，kitty的数据和挂单都被移除
+		// 只有kitty的拥有者可以移除kitty
+		assert_noop!(KittiesModule::remove_kitty(Origin::signed(1),0),Error::<Test>::NotOwner);
+		assert_ok!(KittiesModule::remove_kitty(Origin::signed(0),0));
+		assert_eq!(Kitties::<Test>::get(0),None);
+		assert!(!KittyMarket::<Test>::contains_key(0));
+		// 拥有者和账户的kittyId列表都被清理，kitty和挂单的质押都被退回
+		assert!(!Owner::<Test>::contains_key(0));
+		assert!(!KittyBabies::<Test>::contains_key(0));
+		assert_eq!(Balances::reserved_balance(0),0);
+		System::assert_last_event(Event::KittiesModule(crate::Event::RemovedKitty(0,0)));
+		// kittyId不会被复用
+		create_kitty(0);
+		assert_eq!(Owner::<Test>::get(1),Some(0));
+		assert_ok!(KittiesModule::do_try_state());
+	})
+}
+
+/// 正在拍卖的kitty不能移除
+#[test]
+fn remove_kitty_in_auction_failed(){
+	new_test_ext().execute_with(|| {
+		create_kitty(0);
+		assert_ok!(KittiesModule::create_auction(Origin::signed(0),0,20,5,5));
+		assert_noop!(KittiesModule::remove_kitty(Origin::signed(0),0),Error::<Test>::KittyInAuction);
+	})
+}
+
+/// 交易和拍卖之后各个存储项保持一致
+#[test]
+fn try_state_after_trading_work(){
+	new_test_ext().execute_with(|| {
+		for owner in 0..3 {
+			create_kitty(owner);
+		}
+		assert_ok!(KittiesModule::transfer(Origin::signed(0),1,0));
+		breed_kitty(1,0,1);
+		assert_ok!(KittiesModule::sell_kitties(Origin::signed(1),3,20));
+		assert_ok!(KittiesModule::buy_kitties(Origin::signed(2),3,20));
+		assert_ok!(KittiesModule::create_auction(Origin::signed(2),2,20,5,5));
+		assert_ok!(KittiesModule::bid(Origin::signed(0),2,20));
+		System::set_block_number(5);
+		KittiesModule::on_initialize(5);
+		assert_ok!(KittiesModule::remove_kitty(Origin::signed(1),1));
+		assert_ok!(KittiesModule::do_try_state());
+	})
+}
+
+/// 英式拍卖的测试
+#[test]
+fn auction_work(){
+	new_test_ext().execute_with(|| {
+		create_kitty(0);
+		// 账户0拍卖kitty 0，保留价20，最低加价5，在区块5结束
+		assert_ok!(KittiesModule::create_auction(Origin::signed(0),0,20,5,5));
+		System::assert_last_event(Event::KittiesModule(crate::Event::AuctionCreated(0,0,20,5)));
+		// 正在拍卖的kitty不能转移和挂单
+		assert_noop!(KittiesModule::transfer(Origin::signed(0),1,0),Error::<Test>::KittyInAuction);
+		assert_noop!(KittiesModule::sell_kitties(Origin::signed(0),0,20),Error::<Test>::KittyInAuction);
+
+		// 低于保留价的出价无效
+		assert_noop!(KittiesModule::bid(Origin::signed(1),0,19),Error::<Test>::BidTooLow);
+		assert_ok!(KittiesModule::bid(Origin::signed(1),0,20));
+		// 出价以及kitty的质押都被质押
+		assert_eq!(Balances::reserved_balance(1),24);
+		// 加价低于最低加价的出价无效
+		assert_noop!(KittiesModule::bid(Origin::signed(2),0,24),Error::<Test>::BidTooLow);
+		assert_ok!(KittiesModule::bid(Origin::signed(2),0,30));
+		System::assert_last_event(Event::KittiesModule(crate::Event::BidPlaced(0,2,30)));
+		// 被超过的出价被退回
+		assert_eq!(Balances::reserved_balance(1),0);
+		assert_eq!(Balances::reserved_balance(2),34);
+
+		// 在结束区块结算拍卖
+		System::set_block_number(5);
+		KittiesModule::on_initialize(5);
+		System::assert_last_event(Event::KittiesModule(crate::Event::AuctionSettled(0,0,2,30)));
+		assert_eq!(Owner::<Test>::get(0),Some(2));
+		assert_eq!(KittyBabies::<Test>::get(0).len(),0);
+		assert_eq!(KittyBabies::<Test>::get(2),vec![0]);
+		assert!(!Auctions::<Test>::contains_key(0));
+		// 卖家收到扣除10%手续费后的27个代币，并退回kitty的质押
+		assert_eq!(Balances::free_balance(0),223);
+		assert_eq!(Balances::reserved_balance(0),0);
+		// 买家支付30个代币，kitty的质押仍然保留
+		assert_eq!(Balances::free_balance(2),366);
+		assert_eq!(Balances::reserved_balance(2),4);
+		assert_eq!(Balances::free_balance(TreasuryAccount::get()),3);
+	})
+}
+
+/// 没有人出价的拍卖流拍
+#[test]
+fn auction_expired_work(){
+	new_test_ext().execute_with(|| {
+		create_kitty(0);
+		assert_ok!(KittiesModule::create_auction(Origin::signed(0),0,20,5,5));
+		// 到达结束区块后不能再出价
+		System::set_block_number(5);
+		assert_noop!(KittiesModule::bid(Origin::signed(1),0,20),Error::<Test>::AuctionEnded);
+
+		KittiesModule::on_initialize(5);
+		System::assert_last_event(Event::KittiesModule(crate::Event::AuctionExpired(0)));
+		assert_eq!(Owner::<Test>::get(0),Some(0));
+		assert_eq!(Balances::reserved_balance(0),4);
+		assert_noop!(KittiesModule::bid(Origin::signed(1),0,20),Error::<Test>::AuctionNotFound);
+		// 流拍后可以重新挂单
+		assert_ok!(KittiesModule::sell_kitties(Origin::signed(0),0,20));
+	})
+}
+
+/// 最低加价为0时，和最高出价相同的出价不能取代最高出价
+#[test]
+fn auction_equal_bid_failed(){
+	new_test_ext().execute_with(|| {
+		create_kitty(0);
+		assert_ok!(KittiesModule::create_auction(Origin::signed(0),0,20,0,5));
+		assert_ok!(KittiesModule::bid(Origin::signed(1),0,20));
+		assert_noop!(KittiesModule::bid(Origin::signed(2),0,20),Error::<Test>::BidTooLow);
+		assert_ok!(KittiesModule::bid(Origin::signed(2),0,21));
+		System::assert_last_event(Event::KittiesModule(crate::Event::BidPlaced(0,2,21)));
+	})
+}
+
+/// 最高出价者的质押被扣除后，拍卖结算失败，kitty仍然属于卖家
+#[test]
+fn auction_settle_failed(){
+	new_test_ext().execute_with(|| {
+		create_kitty(0);
+		assert_ok!(KittiesModule::create_auction(Origin::signed(0),0,20,5,5));
+		assert_ok!(KittiesModule::bid(Origin::signed(1),0,20));
+		assert_eq!(Balances::reserved_balance(1),24);
+		// 出价的质押被其他模块扣除了10个代币，不足以支付出价
+		let (_, unslashed) = Balances::slash_reserved(&1,10);
+		assert_eq!(unslashed,0);
+
+		System::set_block_number(5);
+		KittiesModule::on_initialize(5);
+		System::assert_last_event(Event::KittiesModule(crate::Event::AuctionFailed(0,1)));
+		assert_eq!(Owner::<Test>::get(0),Some(0));
+		assert!(!Auctions::<Test>::contains_key(0));
+		// 卖家没有收到任何代币，kitty的质押仍然保留
+		assert_eq!(Balances::free_balance(0),196);
+		assert_eq!(Balances::reserved_balance(0),4);
+		// 最高出价者剩余的质押被退回
+		assert_eq!(Balances::free_balance(1),290);
+		assert_eq!(Balances::reserved_balance(1),0);
+		assert_eq!(Balances::free_balance(TreasuryAccount::get()),0);
+		assert_ok!(KittiesModule::do_try_state());
+	})
+}
+
+/// 发起拍卖和出价失败的测试
+#[test]
+fn auction_failed(){
+	new_test_ext().execute_with(|| {
+		for owner in 0..3 {
+			create_kitty(owner);
+		}
+		assert_noop!(KittiesModule::create_auction(Origin::signed(1),0,20,5,5),Error::<Test>::NotOwner);
+		// 结束区块必须晚于当前区块
+		assert_noop!(KittiesModule::create_auction(Origin::signed(0),0,20,5,1),Error::<Test>::InvalidAuctionEnd);
+		// 挂单的kitty不能拍卖
+		assert_ok!(KittiesModule::sell_kitties(Origin::signed(0),0,20));
+		assert_noop!(KittiesModule::create_auction(Origin::signed(0),0,20,5,5),Error::<Test>::AlreadyListed);
+		assert_ok!(KittiesModule::cancel_listing(Origin::signed(0),0));
+
+		assert_ok!(KittiesModule::create_auction(Origin::signed(0),0,20,5,5));
+		assert_noop!(KittiesModule::create_auction(Origin::signed(0),0,20,5,6),Error::<Test>::KittyInAuction);
+		// 卖家不能对自己的拍卖出价
+		assert_noop!(KittiesModule::bid(Origin::signed(0),0,20),Error::<Test>::BuyOwnKitty);
+		// 余额不足以支付出价
+		assert_noop!(KittiesModule::bid(Origin::signed(1),0,1000),Error::<Test>::NoSufficientBalance);
+		// 同一个区块最多结束2个拍卖
+		assert_ok!(KittiesModule::create_auction(Origin::signed(1),1,20,5,5));
+		assert_noop!(KittiesModule::create_auction(Origin::signed(2),2,20,5,5),Error::<Test>::TooManyAuctions);
+	})
+}
+
+/// 创建count个kitty，账户0和账户1轮流创建，并以(10 * (id + 1))的价格挂单
+fn create_listed_kitties(count: u64){
+	for id in 0..count {
+		let owner = id % 2;
+		create_kitty(owner);
+		assert_ok!(KittiesModule::sell_kitties(Origin::signed(owner),id,10 * (id as u128 + 1)));
+	}
+}
+
+fn market_query(limit: u32) -> MarketQuery<u64, u128>{
+	MarketQuery{
+		owner: None,
+		min_price: None,
+		max_price: None,
+		sort: MarketSortOrder::IndexAsc,
+		cursor: None,
+		limit,
+	}
+}
+
+fn page_ids(page: &MarketPage<u64, u128>) -> Vec<u64>{
+	page.items.iter().map(|item| item.kitty_index).collect()
+}
+
+/// 没有创建过kitty时查询交易市场不会panic
+#[test]
+fn query_empty_market_work(){
+	new_test_ext().execute_with(|| {
+		assert!(KittiesModule::query_kittiy_market_info().is_empty());
+		let page = KittiesModule::query_kitty_market(market_query(10));
+		assert!(page.items.is_empty());
+		assert_eq!(page.next_cursor, None);
+		assert!(KittiesModule::query_owned_kitties(&0, None, 10).items.is_empty());
+	})
+}
+
+/// 交易市场只返回挂单的kitty
+#[test]
+fn query_kitty_market_info_work(){
+	new_test_ext().execute_with(|| {
+		create_listed_kitties(3);
+		// 没有挂单的kitty不会出现在交易市场中
+		create_kitty(2);
+
+		let ids: Vec<u64> = KittiesModule::query_kittiy_market_info().iter().map(|item| item.kitty_index).collect();
+		assert_eq!(ids, vec![2, 1, 0]);
+	})
+}
+
+/// 按游标分页查询交易市场
+#[test]
+fn query_kitty_market_pagination_work(){
+	new_test_ext().execute_with(|| {
+		create_listed_kitties(5);
+
+		let page = KittiesModule::query_kitty_market(market_query(2));
+		assert_eq!(page_ids(&page), vec![0, 1]);
+		assert_eq!(page.next_cursor, Some(MarketCursor{ price: 20, kitty_index: 1 }));
+
+		let mut query = market_query(2);
+		query.cursor = page.next_cursor;
+		let page = KittiesModule::query_kitty_market(query.clone());
+		assert_eq!(page_ids(&page), vec![2, 3]);
+
+		query.cursor = page.next_cursor;
+		let page = KittiesModule::query_kitty_market(query);
+		assert_eq!(page_ids(&page), vec![4]);
+		// 最后一页没有游标
+		assert_eq!(page.next_cursor, None);
+	})
+}
+
+/// 按所有者、价格区间过滤并排序
+#[test]
+fn query_kitty_market_filter_and_sort_work(){
+	new_test_ext().execute_with(|| {
+		create_listed_kitties(5);
+
+		// 账户1挂单的kitty为1和3
+		let mut query = market_query(10);
+		query.owner = Some(1);
+		assert_eq!(page_ids(&KittiesModule::query_kitty_market(query)), vec![1, 3]);
+
+		// 价格在[20, 40]之间，按价格从高到低
+		let mut query = market_query(10);
+		query.min_price = Some(20);
+		query.max_price = Some(40);
+		query.sort = MarketSortOrder::PriceDesc;
+		assert_eq!(page_ids(&KittiesModule::query_kitty_market(query)), vec![3, 2, 1]);
+
+		// 按价格从低到高分页
+		let mut query = market_query(2);
+		query.sort = MarketSortOrder::PriceAsc;
+		// 修改kitty 4的价格为最低
+		assert_ok!(KittiesModule::sell_kitties(Origin::signed(0),4,5));
+		let page = KittiesModule::query_kitty_market(query.clone());
+		assert_eq!(page_ids(&page), vec![4, 0]);
+		query.cursor = page.next_cursor;
+		assert_eq!(page_ids(&KittiesModule::query_kitty_market(query)), vec![1, 2]);
+	})
+}
+
+/// 每页只从有序索引中读取limit个挂单，被过滤掉的挂单同样计入
+#[test]
+fn query_kitty_market_reads_at_most_limit_work(){
+	new_test_ext().execute_with(|| {
+		// 挂单价格分别为10、20、...、60
+		create_listed_kitties(6);
+		assert_eq!(KittiesModule::market_index_page(MarketSortOrder::IndexAsc, None, 2).len(), 2);
+
+		// 前两个挂单的价格都低于50，这一页没有结果，但仍然返回游标
+		let mut query = market_query(2);
+		query.min_price = Some(50);
+		let page = KittiesModule::query_kitty_market(query.clone());
+		assert!(page.items.is_empty());
+		assert_eq!(page.next_cursor, Some(MarketCursor{ price: 20, kitty_index: 1 }));
+
+		query.cursor = Some(MarketCursor{ price: 40, kitty_index: 3 });
+		let page = KittiesModule::query_kitty_market(query.clone());
+		assert_eq!(page_ids(&page), vec![4, 5]);
+		query.cursor = page.next_cursor;
+		assert_eq!(KittiesModule::query_kitty_market(query).next_cursor, None);
+
+		// 重新挂单只更新索引，不会留下旧价格的索引
+		assert_ok!(KittiesModule::sell_kitties(Origin::signed(1),5,1));
+		assert_eq!(ListingIndex::<Test>::iter().count(), 6 * 4);
+		let mut query = market_query(2);
+		query.sort = MarketSortOrder::PriceDesc;
+		assert_eq!(page_ids(&KittiesModule::query_kitty_market(query)), vec![4, 3]);
+
+		// 取消挂单后从索引中移除
+		assert_ok!(KittiesModule::cancel_listing(Origin::signed(0),4));
+		assert_eq!(ListingIndex::<Test>::iter().count(), 5 * 4);
+	})
+}
+
+/// 分页查询账户拥有的kitty
+#[test]
+fn query_owned_kitties_work(){
+	new_test_ext().execute_with(|| {
+		create_listed_kitties(5);
+		// 账户0再创建一个没有挂单的kitty 5
+		create_kitty(0);
+
+		let page = KittiesModule::query_owned_kitties(&0, None, 2);
+		let ids: Vec<u64> = page.items.iter().map(|item| item.kitty_index).collect();
+		assert_eq!(ids, vec![0, 2]);
+		assert_eq!(page.items[0].price, Some(10));
+		assert_eq!(page.next_cursor, Some(2));
+
+		let page = KittiesModule::query_owned_kitties(&0, page.next_cursor, 2);
+		let ids: Vec<u64> = page.items.iter().map(|item| item.kitty_index).collect();
+		assert_eq!(ids, vec![4, 5]);
+		assert_eq!(page.items[1].price, None);
+		assert_eq!(page.next_cursor, None);
+	})
+}
+
+/// 解码dna的外观属性和稀有度
+#[test]
+fn decode_dna_work(){
+	let mut dna = [0u8; 16];
+	dna[crate::dna::EYES] = 153;
+	dna[crate::dna::COLOUR] = 217;
+	dna[crate::dna::PATTERN] = 255;
+	let traits = crate::dna::decode(&dna);
+	assert_eq!(traits.body, TraitValue{ tier: RarityTier::Common, variant: 0 });
+	assert_eq!(traits.eyes, TraitValue{ tier: RarityTier::Uncommon, variant: 1 });
+	assert_eq!(traits.colour, TraitValue{ tier: RarityTier::Rare, variant: 1 });
+	assert_eq!(traits.pattern, TraitValue{ tier: RarityTier::Legendary, variant: 3 });
+	assert_eq!(traits.rarity_score, 18);
+}
+
+/// 稀有度较低的基因为显性，隐性基因会被保留
+#[test]
+fn inherit_dominant_recessive_work(){
+	let mut dna_1 = [0u8; 16];
+	let mut dna_2 = [0u8; 16];
+	// body基因：父为(普通10, 传说250)，母为(稀有230, 普通20)
+	dna_1[0] = 10;
+	dna_1[1] = 250;
+	dna_2[0] = 230;
+	dna_2[1] = 20;
+	dna_1[8] = 0xAA;
+	dna_2[8] = 0x55;
+
+	let mut random = [0u8; 16];
+	random[8] = 0xF0;
+	// 继承父的显性基因10和母的显性基因230，普通的10为显性
+	let child = crate::dna::inherit(&dna_1, &dna_2, &random);
+	assert_eq!(&child[0..2], &[10, 230]);
+	// 隐藏基因按位混合
+	assert_eq!(child[8], 0xA5);
+
+	// 继承父的隐性基因250和母的隐性基因20，普通的20为显性，传说基因成为隐性
+	random[0] = 0b011;
+	assert_eq!(&crate::dna::inherit(&dna_1, &dna_2, &random)[0..2], &[20, 250]);
+
+	// 稀有度相同时由随机数决定显性基因
+	random[0] = 0b110;
+	assert_eq!(&crate::dna::inherit(&dna_1, &dna_2, &random)[0..2], &[10, 20]);
+	random[0] = 0b010;
+	assert_eq!(&crate::dna::inherit(&dna_1, &dna_2, &random)[0..2], &[20, 10]);
+}
+
+/// 查询kitty的外观属性
+#[test]
+fn query_kitty_traits_work(){
+	new_test_ext().execute_with(|| {
+		create_kitty(0);
+		let dna = Kitties::<Test>::get(0).unwrap().0;
+		assert_eq!(KittiesModule::query_kitty_traits(0), Some(crate::dna::decode(&dna)));
+		assert_eq!(KittiesModule::query_kitty_traits(1), None);
+	})
+}
+
+/// 兄弟平行链的来源
+fn sibling(para: u32) -> Origin {
+	cumulus_pallet_xcm::Origin::SiblingParachain(para.into()).into()
+}
+
+/// 发往兄弟平行链的Transact消息
+fn transact_to(para: u32, call: crate::Call<Test>) -> (MultiLocation, Xcm<()>) {
+	(
+		(1, Junction::Parachain(para)).into(),
+		Xcm(vec![Transact {
+			origin_type: OriginKind::Native,
+			require_weight_at_most: XcmWeightAtMost::get(),
+			call: Call::KittiesModule(call).encode().into(),
+		}]),
+	)
+}
+
+/// 跨链转移kitty时锁定本链的kitty，目标平行链返回后解锁
+#[test]
+fn transfer_cross_chain_work(){
+	new_test_ext().execute_with(|| {
+		create_kitty(0);
+		assert_ok!(KittiesModule::sell_kitties(Origin::signed(0),0,20));
+		assert_ok!(KittiesModule::grant_sire_rights(Origin::signed(0),0,2));
+		let kitty = Kitties::<Test>::get(0).unwrap();
+
+		// 只有拥有者可以跨链转移kitty
+		assert_noop!(KittiesModule::transfer_cross_chain(Origin::signed(1),0,5),Error::<Test>::NotOwner);
+		assert_ok!(KittiesModule::transfer_cross_chain(Origin::signed(0),0,5));
+		System::assert_last_event(Event::KittiesModule(crate::Event::KittySentCrossChain(0,0,4000.into(),5)));
+		assert_eq!(
+			sent_xcm(),
+			vec![transact_to(4000, crate::Call::mint_wrapped{ remote_kitty_id: 0, kitty, beneficiary: 5 })]
+		);
+		// kitty被锁定，挂单和繁殖权失效
+		assert_eq!(LockedKitties::<Test>::get(0),Some(4000.into()));
+		assert!(!KittyMarket::<Test>::contains_key(0));
+		assert!(!SireRights::<Test>::contains_key(0,2));
+		assert_eq!(Owner::<Test>::get(0),Some(0));
+		assert_noop!(KittiesModule::transfer(Origin::signed(0),1,0),Error::<Test>::KittyLocked);
+		assert_noop!(KittiesModule::sell_kitties(Origin::signed(0),0,20),Error::<Test>::KittyLocked);
+		assert_noop!(KittiesModule::remove_kitty(Origin::signed(0),0),Error::<Test>::KittyLocked);
+		assert_noop!(KittiesModule::transfer_cross_chain(Origin::signed(0),0,5),Error::<Test>::KittyLocked);
+		assert_ok!(KittiesModule::do_try_state());
+
+		// 其他平行链不能解锁kitty
+		assert_ok!(KittiesModule::unlock_kitty(sibling(2000),0,Some(1)));
+		System::assert_last_event(Event::KittiesModule(crate::Event::UnknownKittyUnlock(2000.into(),0)));
+		assert!(LockedKitties::<Test>::contains_key(0));
+
+		// kitty返回本链时解锁给beneficiary
+		assert_ok!(KittiesModule::unlock_kitty(sibling(4000),0,Some(1)));
+		System::assert_last_event(Event::KittiesModule(crate::Event::KittyUnlocked(0,1)));
+		assert!(!LockedKitties::<Test>::contains_key(0));
+		assert_eq!(Owner::<Test>::get(0),Some(1));
+		assert_eq!(KittyBabies::<Test>::get(1),vec![0]);
+		assert_eq!(Balances::reserved_balance(0),0);
+		assert_eq!(Balances::reserved_balance(1),4);
+		assert_ok!(KittiesModule::do_try_state());
+	})
+}
+
+/// 跨链消息发送失败或者目标平行链铸造失败时，kitty仍然属于原拥有者
+#[test]
+fn transfer_cross_chain_rollback_work(){
+	new_test_ext().execute_with(|| {
+		create_kitty(0);
+
+		set_xcm_send_fails(true);
+		assert_noop!(KittiesModule::transfer_cross_chain(Origin::signed(0),0,5),Error::<Test>::XcmSendError);
+		set_xcm_send_fails(false);
+
+		// 目标平行链铸造失败时，kitty解锁给原拥有者
+		assert_ok!(KittiesModule::transfer_cross_chain(Origin::signed(0),0,5));
+		assert_ok!(KittiesModule::unlock_kitty(sibling(4000),0,None));
+		System::assert_last_event(Event::KittiesModule(crate::Event::KittyUnlocked(0,0)));
+		assert_eq!(Owner::<Test>::get(0),Some(0));
+		assert_eq!(Balances::reserved_balance(0),4);
+		assert_ok!(KittiesModule::do_try_state());
+	})
+}
+
+/// kitty返回本链时无法为beneficiary质押，kitty保持锁定直到beneficiary领取
+#[test]
+fn unlock_kitty_to_unfunded_beneficiary_work(){
+	new_test_ext().execute_with(|| {
+		create_kitty(0);
+		assert_ok!(KittiesModule::transfer_cross_chain(Origin::signed(0),0,5));
+
+		// 账户42没有余额，kitty不会退回给原拥有者
+		assert_ok!(KittiesModule::unlock_kitty(sibling(4000),0,Some(42)));
+		System::assert_last_event(Event::KittiesModule(crate::Event::KittyAwaitingClaim(0,42)));
+		assert_eq!(UnclaimedKitties::<Test>::get(0),Some(42));
+		assert!(LockedKitties::<Test>::contains_key(0));
+		assert_eq!(Owner::<Test>::get(0),Some(0));
+		assert_noop!(KittiesModule::transfer(Origin::signed(0),1,0),Error::<Test>::KittyLocked);
+		assert_ok!(KittiesModule::do_try_state());
+
+		// 等待领取的kitty不能再次解锁
+		assert_ok!(KittiesModule::unlock_kitty(sibling(4000),0,None));
+		System::assert_last_event(Event::KittiesModule(crate::Event::UnknownKittyUnlock(4000.into(),0)));
+		assert_eq!(Owner::<Test>::get(0),Some(0));
+
+		assert_noop!(KittiesModule::claim_kitty(Origin::signed(1),0),Error::<Test>::NotBeneficiary);
+		assert_noop!(KittiesModule::claim_kitty(Origin::signed(42),0),Error::<Test>::NoSufficientBalance);
+		assert_noop!(KittiesModule::claim_kitty(Origin::signed(42),1),Error::<Test>::NothingToClaim);
+
+		// beneficiary有足够的余额后领取kitty
+		let _ = Balances::deposit_creating(&42,10);
+		assert_ok!(KittiesModule::claim_kitty(Origin::signed(42),0));
+		System::assert_last_event(Event::KittiesModule(crate::Event::KittyUnlocked(0,42)));
+		assert!(!UnclaimedKitties::<Test>::contains_key(0));
+		assert!(!LockedKitties::<Test>::contains_key(0));
+		assert_eq!(Owner::<Test>::get(0),Some(42));
+		assert_eq!(KittyBabies::<Test>::get(42),vec![0]);
+		assert_eq!(Balances::reserved_balance(0),0);
+		assert_eq!(Balances::reserved_balance(42),4);
+		assert_ok!(KittiesModule::do_try_state());
+	})
+}
+
+/// 目标平行链的Transact执行失败时，root可以将kitty解锁给原拥有者
+#[test]
+fn force_unlock_kitty_work(){
+	new_test_ext().execute_with(|| {
+		create_kitty(0);
+		assert_noop!(KittiesModule::force_unlock_kitty(Origin::root(),0),Error::<Test>::KittyNotLocked);
+		assert_ok!(KittiesModule::transfer_cross_chain(Origin::signed(0),0,5));
+
+		assert_noop!(
+			KittiesModule::force_unlock_kitty(Origin::signed(0),0),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_ok!(KittiesModule::force_unlock_kitty(Origin::root(),0));
+		System::assert_last_event(Event::KittiesModule(crate::Event::KittyUnlocked(0,0)));
+		assert!(!LockedKitties::<Test>::contains_key(0));
+		assert_eq!(Owner::<Test>::get(0),Some(0));
+		assert_eq!(Balances::reserved_balance(0),4);
+		// 解锁后的kitty可以正常转移，之后到达的解锁消息被忽略
+		assert_ok!(KittiesModule::transfer(Origin::signed(0),1,0));
+		assert_ok!(KittiesModule::unlock_kitty(sibling(4000),0,Some(5)));
+		System::assert_last_event(Event::KittiesModule(crate::Event::UnknownKittyUnlock(4000.into(),0)));
+		assert_eq!(Owner::<Test>::get(0),Some(1));
+
+		// 等待领取的kitty也可以强制解锁
+		assert_ok!(KittiesModule::transfer_cross_chain(Origin::signed(1),0,5));
+		assert_ok!(KittiesModule::unlock_kitty(sibling(4000),0,Some(42)));
+		assert_ok!(KittiesModule::force_unlock_kitty(Origin::root(),0));
+		assert!(!UnclaimedKitties::<Test>::contains_key(0));
+		assert_eq!(Owner::<Test>::get(0),Some(1));
+		assert_ok!(KittiesModule::do_try_state());
+	})
+}
+
+/// 兄弟平行链转移过来的kitty在本链铸造为包装kitty，转回时销毁
+#[test]
+fn mint_wrapped_work(){
+	new_test_ext().execute_with(|| {
+		let kitty = Kitty([1; 16]);
+		// 只接受来自KittyDestination的消息
+		assert_noop!(
+			KittiesModule::mint_wrapped(sibling(2000),7,kitty.clone(),1),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_noop!(
+			KittiesModule::mint_wrapped(Origin::signed(1),7,kitty.clone(),1),
+			sp_runtime::DispatchError::BadOrigin
+		);
+
+		assert_ok!(KittiesModule::mint_wrapped(sibling(4000),7,kitty.clone(),1));
+		System::assert_last_event(Event::KittiesModule(crate::Event::WrappedKittyMinted(4000.into(),7,0)));
+		assert_eq!(Kitties::<Test>::get(0),Some(kitty));
+		assert_eq!(Owner::<Test>::get(0),Some(1));
+		assert_eq!(WrappedKitties::<Test>::get(0),Some((4000.into(),7)));
+		assert_eq!(Balances::reserved_balance(1),4);
+
+		// 转回来源平行链时销毁包装kitty，并在来源平行链解锁原来的kitty
+		assert_ok!(KittiesModule::transfer_cross_chain(Origin::signed(1),0,5));
+		assert_eq!(
+			sent_xcm(),
+			vec![transact_to(4000, crate::Call::unlock_kitty{ kitty_id: 7, beneficiary: Some(5) })]
+		);
+		assert!(!Kitties::<Test>::contains_key(0));
+		assert!(!WrappedKitties::<Test>::contains_key(0));
+		assert_eq!(Balances::reserved_balance(1),0);
+		assert_ok!(KittiesModule::do_try_state());
+	})
+}
+
+/// 无法为接收者质押时，通知来源平行链将kitty退回原拥有者
+#[test]
+fn mint_wrapped_failed_work(){
+	new_test_ext().execute_with(|| {
+		// 账户42没有余额
+		assert_ok!(KittiesModule::mint_wrapped(sibling(4000),7,Kitty([1; 16]),42));
+		System::assert_last_event(Event::KittiesModule(crate::Event::WrappedKittyMintFailed(4000.into(),7)));
+		assert_eq!(KittiesCount::<Test>::get(),None);
+		assert_eq!(
+			sent_xcm(),
+			vec![transact_to(4000, crate::Call::unlock_kitty{ kitty_id: 7, beneficiary: None })]
+		);
+	})
+}
+
+#[test]
+fn set_metadata_uri_work(){
+	new_test_ext().execute_with(|| {
+		create_kitty(1);
+
+		assert_noop!(
+			KittiesModule::set_metadata_uri(Origin::signed(2),0,Some(b"ipfs://kitty".to_vec())),
+			Error::<Test>::NotOwner
+		);
+		// 超过MaxMetadataUriLen
+		assert_noop!(
+			KittiesModule::set_metadata_uri(Origin::signed(1),0,Some(vec![b'a'; 17])),
+			Error::<Test>::MetadataUriTooLong
+		);
+
+		assert_ok!(KittiesModule::set_metadata_uri(Origin::signed(1),0,Some(b"ipfs://kitty".to_vec())));
+		System::assert_last_event(Event::KittiesModule(crate::Event::MetadataUriSet(0,Some(b"ipfs://kitty".to_vec()))));
+		assert_eq!(KittiesModule::metadata_uri(0).map(|uri| uri.into_inner()),Some(b"ipfs://kitty".to_vec()));
+
+		assert_ok!(KittiesModule::set_metadata_uri(Origin::signed(1),0,None));
+		System::assert_last_event(Event::KittiesModule(crate::Event::MetadataUriSet(0,None)));
+		assert_eq!(KittiesModule::metadata_uri(0),None);
+
+		// 销毁kitty时清除元数据
+		assert_ok!(KittiesModule::set_metadata_uri(Origin::signed(1),0,Some(b"ipfs://kitty".to_vec())));
+		assert_ok!(KittiesModule::remove_kitty(Origin::signed(1),0));
+		assert_eq!(KittiesModule::metadata_uri(0),None);
+		assert_ok!(KittiesModule::do_try_state());
+	})
+}
+
+#[test]
+fn set_metadata_uri_locked_kitty_failed(){
+	new_test_ext().execute_with(|| {
+		create_kitty(1);
+		assert_ok!(KittiesModule::transfer_cross_chain(Origin::signed(1),0,5));
+		assert_noop!(
+			KittiesModule::set_metadata_uri(Origin::signed(1),0,Some(b"ipfs://kitty".to_vec())),
+			Error::<Test>::KittyLocked
+		);
+		// 通过nonfungibles的属性也不能修改锁定的kitty的元数据URI
+		assert_noop!(
+			<KittiesModule as nonfungibles::Mutate<u64>>::set_attribute(&(),&0,METADATA_URI_KEY,b"ipfs://kitty"),
+			Error::<Test>::KittyLocked
+		);
+		assert_eq!(KittiesModule::metadata_uri(0),None);
+	})
+}
+
+#[test]
+fn nonfungibles_inspect_and_transfer_work(){
+	new_test_ext().execute_with(|| {
+		create_kitty(1);
+		assert_ok!(KittiesModule::set_metadata_uri(Origin::signed(1),0,Some(b"ipfs://kitty".to_vec())));
+
+		assert_eq!(<KittiesModule as nonfungibles::Inspect<u64>>::owner(&(),&0),Some(1));
+		assert_eq!(<KittiesModule as nonfungibles::Inspect<u64>>::owner(&(),&1),None);
+		assert_eq!(
+			<KittiesModule as nonfungibles::Inspect<u64>>::attribute(&(),&0,METADATA_URI_KEY),
+			Some(b"ipfs://kitty".to_vec())
+		);
+		assert_eq!(<KittiesModule as nonfungibles::Inspect<u64>>::attribute(&(),&0,b"name"),None);
+		assert!(<KittiesModule as nonfungibles::Inspect<u64>>::can_transfer(&(),&0));
+		assert!(!<KittiesModule as nonfungibles::Inspect<u64>>::can_transfer(&(),&1));
+
+		assert_ok!(<KittiesModule as nonfungibles::Transfer<u64>>::transfer(&(),&0,&2));
+		System::assert_last_event(Event::KittiesModule(crate::Event::KittyTransfer(1,2,0)));
+		assert_eq!(Owner::<Test>::get(0),Some(2));
+		assert_eq!(Balances::reserved_balance(1),0);
+		assert_eq!(Balances::reserved_balance(2),4);
+		assert_noop!(
+			<KittiesModule as nonfungibles::Transfer<u64>>::transfer(&(),&1,&2),
+			Error::<Test>::InvalidKittyIndex
+		);
+
+		// 锁定的kitty不能转移
+		assert_ok!(KittiesModule::transfer_cross_chain(Origin::signed(2),0,5));
+		assert!(!<KittiesModule as nonfungibles::Inspect<u64>>::can_transfer(&(),&0));
+		assert_noop!(
+			<KittiesModule as nonfungibles::Transfer<u64>>::transfer(&(),&0,&1),
+			Error::<Test>::KittyLocked
+		);
+	})
+}
+
+#[test]
+fn nonfungibles_mutate_work(){
+	new_test_ext().execute_with(|| {
+		// mint_into和create一样延迟铸造，只能铸造下一个kittyId
+		assert_noop!(
+			<KittiesModule as nonfungibles::Mutate<u64>>::mint_into(&(),&1,&1),
+			Error::<Test>::InvalidKittyIndex
+		);
+		assert_ok!(<KittiesModule as nonfungibles::Mutate<u64>>::mint_into(&(),&0,&1));
+		System::assert_last_event(Event::KittiesModule(crate::Event::MintRequested(1,4)));
+		assert_eq!(Owner::<Test>::get(0),None);
+		reveal_pending_mints();
+		assert_eq!(Owner::<Test>::get(0),Some(1));
+		assert_eq!(Balances::reserved_balance(1),4);
+
+		assert_noop!(
+			<KittiesModule as nonfungibles::Mutate<u64>>::set_attribute(&(),&0,b"name",b"tom"),
+			TokenError::Unsupported
+		);
+		assert_noop!(
+			<KittiesModule as nonfungibles::Mutate<u64>>::set_attribute(&(),&1,METADATA_URI_KEY,b"ipfs://kitty"),
+			Error::<Test>::InvalidKittyIndex
+		);
+		assert_ok!(<KittiesModule as nonfungibles::Mutate<u64>>::set_attribute(&(),&0,METADATA_URI_KEY,b"ipfs://kitty"));
+		assert_eq!(KittiesModule::metadata_uri(0).map(|uri| uri.into_inner()),Some(b"ipfs://kitty".to_vec()));
+
+		assert_ok!(<KittiesModule as nonfungibles::Mutate<u64>>::burn_from(&(),&0));
+		System::assert_last_event(Event::KittiesModule(crate::Event::RemovedKitty(1,0)));
+		assert!(!Kitties::<Test>::contains_key(0));
+		assert_eq!(KittiesModule::metadata_uri(0),None);
+		assert_eq!(Balances::reserved_balance(1),0);
+		assert_noop!(
+			<KittiesModule as nonfungibles::Mutate<u64>>::burn_from(&(),&0),
+			Error::<Test>::InvalidKittyIndex
+		);
+		assert_ok!(KittiesModule::do_try_state());
+	})
+}
+
+#[test]
+fn approve_and_transfer_from_work(){
+	new_test_ext().execute_with(|| {
+		create_kitty(0);
+
+		assert_noop!(KittiesModule::approve(Origin::signed(1),0,Some(1)),Error::<Test>::NotOwner);
+		assert_noop!(KittiesModule::approve(Origin::signed(0),0,Some(0)),Error::<Test>::ApproveToSelf);
+		assert_noop!(KittiesModule::approve(Origin::signed(0),1,Some(1)),Error::<Test>::InvalidKittyIndex);
+		// 没有授权时不能转移
+		assert_noop!(KittiesModule::transfer_from(Origin::signed(1),0,2,0),Error::<Test>::NotApproved);
+
+		assert_ok!(KittiesModule::approve(Origin::signed(0),0,Some(1)));
+		System::assert_last_event(Event::KittiesModule(crate::Event::Approval(0,Some(1),0)));
+		assert_eq!(KittiesModule::approved(0),Some(1));
+
+		// from必须是kitty的拥有者
+		assert_noop!(KittiesModule::transfer_from(Origin::signed(1),2,1,0),Error::<Test>::NotOwner);
+
+		// 被授权的账户转移kitty之后，授权被清除
+		assert_ok!(KittiesModule::transfer_from(Origin::signed(1),0,2,0));
+		System::assert_has_event(Event::KittiesModule(crate::Event::Approval(0,None,0)));
+		System::assert_last_event(Event::KittiesModule(crate::Event::KittyTransfer(0,2,0)));
+		assert_eq!(Owner::<Test>::get(0),Some(2));
+		assert_eq!(KittiesModule::approved(0),None);
+		assert_eq!(Balances::reserved_balance(0),0);
+		assert_eq!(Balances::reserved_balance(2),4);
+		assert_noop!(KittiesModule::transfer_from(Origin::signed(1),2,1,0),Error::<Test>::NotApproved);
+
+		// 取消授权
+		assert_ok!(KittiesModule::approve(Origin::signed(2),0,Some(1)));
+		assert_ok!(KittiesModule::approve(Origin::signed(2),0,None));
+		System::assert_last_event(Event::KittiesModule(crate::Event::Approval(2,None,0)));
+		assert_noop!(KittiesModule::transfer_from(Origin::signed(1),2,1,0),Error::<Test>::NotApproved);
+
+		// 拥有者自己也可以调用transfer_from
+		assert_ok!(KittiesModule::transfer_from(Origin::signed(2),2,1,0));
+		assert_eq!(Owner::<Test>::get(0),Some(1));
+		assert_ok!(KittiesModule::do_try_state());
+	})
+}
+
+#[test]
+fn set_approval_for_all_work(){
+	new_test_ext().execute_with(|| {
+		create_kitty(0);
+		create_kitty(0);
+
+		assert_noop!(KittiesModule::set_approval_for_all(Origin::signed(0),0,true),Error::<Test>::ApproveToSelf);
+
+		assert_ok!(KittiesModule::set_approval_for_all(Origin::signed(0),1,true));
+		System::assert_last_event(Event::KittiesModule(crate::Event::ApprovalForAll(0,1,true)));
+		assert!(KittiesModule::is_operator(&0,&1));
+
+		// 操作者可以转移拥有者的所有kitty，也可以为kitty授权
+		assert_ok!(KittiesModule::transfer_from(Origin::signed(1),0,2,0));
+		assert_eq!(Owner::<Test>::get(0),Some(2));
+		assert_ok!(KittiesModule::approve(Origin::signed(1),1,Some(2)));
+		System::assert_last_event(Event::KittiesModule(crate::Event::Approval(0,Some(2),1)));
+		assert_ok!(KittiesModule::transfer_from(Origin::signed(1),0,1,1));
+		assert_eq!(Owner::<Test>::get(1),Some(1));
+		// kitty转移后操作者对新拥有者的kitty没有权限
+		assert_noop!(KittiesModule::transfer_from(Origin::signed(1),2,1,0),Error::<Test>::NotApproved);
+
+		assert_ok!(KittiesModule::set_approval_for_all(Origin::signed(0),1,false));
+		System::assert_last_event(Event::KittiesModule(crate::Event::ApprovalForAll(0,1,false)));
+		assert!(!KittiesModule::is_operator(&0,&1));
+	})
+}
+
+#[test]
+fn approval_cleared_when_kitty_sold_work(){
+	new_test_ext().execute_with(|| {
+		create_kitty(0);
+		assert_ok!(KittiesModule::approve(Origin::signed(0),0,Some(1)));
+		assert_ok!(KittiesModule::sell_kitties(Origin::signed(0),0,10));
+		assert_ok!(KittiesModule::buy_kitties(Origin::signed(2),0,10));
+		System::assert_has_event(Event::KittiesModule(crate::Event::Approval(0,None,0)));
+		assert_eq!(KittiesModule::approved(0),None);
+
+		// 销毁kitty时清除授权
+		assert_ok!(KittiesModule::approve(Origin::signed(2),0,Some(1)));
+		assert_ok!(KittiesModule::remove_kitty(Origin::signed(2),0));
+		assert_eq!(KittiesModule::approved(0),None);
+		assert_ok!(KittiesModule::do_try_state());
+	})
+}
+
+/// 版本1的迁移将挂单价格转换为Listing，没有所有者或者价格为0的挂单被移除
+#[test]
+fn migration_v1_translates_listings_work(){
+	new_test_ext().execute_with(|| {
+		use frame_support::{
+			storage::{unhashed, StorageMap},
+			traits::{GetStorageVersion, StorageVersion},
+		};
+
+		create_kitty(0);
+		create_kitty(1);
+		// 模拟升级前的存储：KittyMarket的值只有价格
+		StorageVersion::new(0).put::<KittiesModule>();
+		unhashed::put(&KittyMarket::<Test>::hashed_key_for(0), &20u128);
+		unhashed::put(&KittyMarket::<Test>::hashed_key_for(1), &0u128);
+		unhashed::put(&KittyMarket::<Test>::hashed_key_for(5), &30u128);
+
+		crate::migrations::v1::migrate::<Test>();
+
+		assert_eq!(KittiesModule::on_chain_storage_version(),1);
+		assert_eq!(KittyMarket::<Test>::get(0),Some(Listing { seller: 0, price: 20, deposit: 0 }));
+		assert_eq!(KittyMarket::<Test>::get(1),None);
+		assert_eq!(KittyMarket::<Test>::get(5),None);
+		// 保留的挂单写入了有序索引
+		assert_eq!(page_ids(&KittiesModule::query_kitty_market(market_query(10))), vec![0]);
+
+		// 再次执行迁移不会改变存储
+		crate::migrations::v1::migrate::<Test>();
+		assert_eq!(KittyMarket::<Test>::get(0),Some(Listing { seller: 0, price: 20, deposit: 0 }));
+	})
+}
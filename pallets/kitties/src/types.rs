@@ -62,3 +62,142 @@ pub struct KittyInfo<AccountId, Balance> {
 	pub price: Balance,
 	pub kitty_dna: Kitty,
 }
+
+/// 交易市场查询结果的排序方式
+#[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub enum MarketSortOrder {
+	/// 按kittyId从小到大
+	IndexAsc,
+	/// 按kittyId从大到小
+	IndexDesc,
+	/// 按价格从低到高，价格相同时按kittyId从小到大
+	PriceAsc,
+	/// 按价格从高到低，价格相同时按kittyId从大到小
+	PriceDesc,
+}
+
+impl Default for MarketSortOrder {
+	fn default() -> Self {
+		MarketSortOrder::IndexAsc
+	}
+}
+
+/// 分页游标，即上一页最后一条记录的价格和kittyId，下一页从它之后开始
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct MarketCursor<Balance> {
+	pub price: Balance,
+	pub kitty_index: KittyIndex,
+}
+
+/// 交易市场的查询条件
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct MarketQuery<AccountId, Balance> {
+	/// 只查询该账户挂单的kitty
+	pub owner: Option<AccountId>,
+	/// 最低价格(包含)
+	pub min_price: Option<Balance>,
+	/// 最高价格(包含)
+	pub max_price: Option<Balance>,
+	pub sort: MarketSortOrder,
+	/// 上一页返回的next_cursor，查询第一页时为None
+	pub cursor: Option<MarketCursor<Balance>>,
+	/// 每页最多读取的挂单数量，最多为MAX_PAGE_SIZE；按条件过滤后返回的数量可能更少
+	pub limit: u32,
+}
+
+/// 交易市场的一页查询结果
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct MarketPage<AccountId, Balance> {
+	pub items: Vec<KittyInfoById<AccountId, Balance>>,
+	/// 读满一页时用于查询下一页的游标，为None时说明已经没有更多挂单
+	pub next_cursor: Option<MarketCursor<Balance>>,
+}
+
+/// 账户拥有的一个kitty，price为挂单价格，没有挂单时为None
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct OwnedKitty<Balance> {
+	pub kitty_index: KittyIndex,
+	pub kitty_dna: Kitty,
+	pub price: Option<Balance>,
+}
+
+/// 账户拥有的kitty的一页查询结果，按kittyId从小到大排列
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct OwnedKittiesPage<Balance> {
+	pub items: Vec<OwnedKitty<Balance>>,
+	/// 还有下一页时，用于查询下一页的游标(本页最后一个kittyId)
+	pub next_cursor: Option<KittyIndex>,
+}
+
+/// 交易市场中的一个挂单
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct Listing<AccountId, Balance> {
+	/// 挂单的卖家，即挂单时kitty的所有者
+	pub seller: AccountId,
+	/// 挂单价格
+	pub price: Balance,
+	/// 卖家挂单时质押的金额，挂单被取消、成交或者失效时退回
+	pub deposit: Balance,
+}
+
+/// 英式拍卖，出价最高且不低于保留价的买家在结束区块得到kitty
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct Auction<AccountId, Balance, BlockNumber> {
+	/// 发起拍卖的卖家，即发起拍卖时kitty的所有者
+	pub seller: AccountId,
+	/// 保留价，低于保留价的出价无效
+	pub reserve_price: Balance,
+	/// 每次出价至少要比当前最高出价高出的金额
+	pub min_increment: Balance,
+	/// 拍卖结束的区块，在该区块开始时结算
+	pub end_block: BlockNumber,
+	/// 当前最高出价的买家以及出价金额
+	pub highest_bid: Option<(AccountId, Balance)>,
+}
+
+/// 等待在揭示区块产生dna的延迟铸造请求
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct PendingMint<AccountId, KittyIndex> {
+	/// 请求铸造并为新kitty质押的账户
+	pub owner: AccountId,
+	/// 请求时分配给新kitty的id，揭示失败时这个id不再使用
+	pub kitty_id: KittyIndex,
+	/// 繁殖时的父母kittyId，揭示时从父母遗传基因；直接创建的kitty为None
+	pub parents: Option<(KittyIndex, KittyIndex)>,
+}
+
+/// kitty的血统信息
+#[derive(PartialEq, Eq, Clone, Default, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct KittyGenealogy<KittyIndex, BlockNumber> {
+	/// 父母的kittyId，直接创建的kitty没有父母
+	pub parents: Option<(KittyIndex, KittyIndex)>,
+	/// 代数，直接创建的kitty为第0代，繁殖出的kitty比父母中较大的代数多1
+	pub generation: u32,
+	/// 出生的区块
+	pub birth_block: BlockNumber,
+	/// 在该区块之前不能再次繁殖
+	pub cooldown_until: BlockNumber,
+}
+
+/// 家谱中的一个kitty
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct FamilyTreeNode<BlockNumber> {
+	pub kitty_index: KittyIndex,
+	pub parents: Option<(KittyIndex, KittyIndex)>,
+	pub generation: u32,
+	pub birth_block: BlockNumber,
+}
@@ -0,0 +1,185 @@
+//! kitty模块(pallet-kitties)各个交易的weight
+//!
+//! 这里的数值是人工估算的，不是benchmark的结果：数据库读写次数按照代码中访问的存储统计并向上取整，
+//! 基础执行时间取了偏保守的整数。kitty模块还没有benchmark，部署到正式链之前需要补充benchmark，
+//! 用生成的结果替换这个文件。
+
+use frame_support::{
+	traits::Get,
+	weights::{constants::RocksDbWeight, RuntimeDbWeight, Weight},
+};
+use sp_std::marker::PhantomData;
+
+/// kitty模块各个交易需要的weight
+pub trait WeightInfo {
+	fn create() -> Weight;
+	fn request_mint() -> Weight;
+	fn transfer() -> Weight;
+	fn breed() -> Weight;
+	fn grant_sire_rights() -> Weight;
+	fn revoke_sire_rights() -> Weight;
+	fn sell_kitties() -> Weight;
+	fn cancel_listing() -> Weight;
+	fn buy_kitties() -> Weight;
+	fn create_auction() -> Weight;
+	fn bid() -> Weight;
+	fn remove_kitty() -> Weight;
+	fn set_metadata_uri() -> Weight;
+	fn approve() -> Weight;
+	fn set_approval_for_all() -> Weight;
+	fn transfer_from() -> Weight;
+	fn transfer_cross_chain() -> Weight;
+	fn mint_wrapped() -> Weight;
+	fn unlock_kitty() -> Weight;
+	fn claim_kitty() -> Weight;
+	fn force_unlock_kitty() -> Weight;
+}
+
+/// 每微秒对应的weight
+const MICROS: Weight = 1_000_000;
+
+/// 估算的weight：基础执行时间(微秒)加上数据库的读写
+fn estimate<W: Get<RuntimeDbWeight>>(micros: Weight, reads: Weight, writes: Weight) -> Weight {
+	(micros * MICROS).saturating_add(W::get().reads_writes(reads, writes))
+}
+
+/// 使用runtime中配置的数据库读写weight
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	// 发起延迟铸造：分配kittyId，读写PendingMints，质押ReservationFee
+	fn create() -> Weight {
+		estimate::<T::DbWeight>(45, 3, 3)
+	}
+	fn request_mint() -> Weight {
+		estimate::<T::DbWeight>(45, 3, 3)
+	}
+	// 转移所有权：读写Owner、双方的KittyBabies和质押，清除挂单、繁殖授权和approve
+	fn transfer() -> Weight {
+		estimate::<T::DbWeight>(70, 9, 9)
+	}
+	// 读取双亲的Kitties、Owner和冷却时间，发起延迟铸造并更新双亲的冷却时间
+	fn breed() -> Weight {
+		estimate::<T::DbWeight>(70, 10, 7)
+	}
+	fn grant_sire_rights() -> Weight {
+		estimate::<T::DbWeight>(30, 3, 1)
+	}
+	fn revoke_sire_rights() -> Weight {
+		estimate::<T::DbWeight>(30, 2, 1)
+	}
+	// 挂单：KittyMarket以及四种排序方式的ListingIndex(更新价格时先删除旧的索引)
+	fn sell_kitties() -> Weight {
+		estimate::<T::DbWeight>(50, 5, 10)
+	}
+	fn cancel_listing() -> Weight {
+		estimate::<T::DbWeight>(40, 2, 6)
+	}
+	// 买家向卖家和交易市场账户付款，转移所有权并清除挂单及其索引
+	fn buy_kitties() -> Weight {
+		estimate::<T::DbWeight>(110, 10, 15)
+	}
+	fn create_auction() -> Weight {
+		estimate::<T::DbWeight>(45, 5, 2)
+	}
+	fn bid() -> Weight {
+		estimate::<T::DbWeight>(55, 4, 3)
+	}
+	fn remove_kitty() -> Weight {
+		estimate::<T::DbWeight>(60, 7, 9)
+	}
+	fn set_metadata_uri() -> Weight {
+		estimate::<T::DbWeight>(35, 3, 2)
+	}
+	fn approve() -> Weight {
+		estimate::<T::DbWeight>(30, 2, 1)
+	}
+	fn set_approval_for_all() -> Weight {
+		estimate::<T::DbWeight>(20, 0, 1)
+	}
+	fn transfer_from() -> Weight {
+		estimate::<T::DbWeight>(75, 10, 9)
+	}
+	// 锁定kitty并通过XCMP发送跨链消息
+	fn transfer_cross_chain() -> Weight {
+		estimate::<T::DbWeight>(110, 9, 7)
+	}
+	fn mint_wrapped() -> Weight {
+		estimate::<T::DbWeight>(65, 4, 6)
+	}
+	fn unlock_kitty() -> Weight {
+		estimate::<T::DbWeight>(65, 6, 7)
+	}
+	fn claim_kitty() -> Weight {
+		estimate::<T::DbWeight>(55, 4, 6)
+	}
+	fn force_unlock_kitty() -> Weight {
+		estimate::<T::DbWeight>(35, 3, 2)
+	}
+}
+
+// 测试中使用，数据库读写按RocksDb计算
+impl WeightInfo for () {
+	fn create() -> Weight {
+		estimate::<RocksDbWeight>(45, 3, 3)
+	}
+	fn request_mint() -> Weight {
+		estimate::<RocksDbWeight>(45, 3, 3)
+	}
+	fn transfer() -> Weight {
+		estimate::<RocksDbWeight>(70, 9, 9)
+	}
+	fn breed() -> Weight {
+		estimate::<RocksDbWeight>(70, 10, 7)
+	}
+	fn grant_sire_rights() -> Weight {
+		estimate::<RocksDbWeight>(30, 3, 1)
+	}
+	fn revoke_sire_rights() -> Weight {
+		estimate::<RocksDbWeight>(30, 2, 1)
+	}
+	fn sell_kitties() -> Weight {
+		estimate::<RocksDbWeight>(50, 5, 10)
+	}
+	fn cancel_listing() -> Weight {
+		estimate::<RocksDbWeight>(40, 2, 6)
+	}
+	fn buy_kitties() -> Weight {
+		estimate::<RocksDbWeight>(110, 10, 15)
+	}
+	fn create_auction() -> Weight {
+		estimate::<RocksDbWeight>(45, 5, 2)
+	}
+	fn bid() -> Weight {
+		estimate::<RocksDbWeight>(55, 4, 3)
+	}
+	fn remove_kitty() -> Weight {
+		estimate::<RocksDbWeight>(60, 7, 9)
+	}
+	fn set_metadata_uri() -> Weight {
+		estimate::<RocksDbWeight>(35, 3, 2)
+	}
+	fn approve() -> Weight {
+		estimate::<RocksDbWeight>(30, 2, 1)
+	}
+	fn set_approval_for_all() -> Weight {
+		estimate::<RocksDbWeight>(20, 0, 1)
+	}
+	fn transfer_from() -> Weight {
+		estimate::<RocksDbWeight>(75, 10, 9)
+	}
+	fn transfer_cross_chain() -> Weight {
+		estimate::<RocksDbWeight>(110, 9, 7)
+	}
+	fn mint_wrapped() -> Weight {
+		estimate::<RocksDbWeight>(65, 4, 6)
+	}
+	fn unlock_kitty() -> Weight {
+		estimate::<RocksDbWeight>(65, 6, 7)
+	}
+	fn claim_kitty() -> Weight {
+		estimate::<RocksDbWeight>(55, 4, 6)
+	}
+	fn force_unlock_kitty() -> Weight {
+		estimate::<RocksDbWeight>(35, 3, 2)
+	}
+}
@@ -0,0 +1,33 @@
+//! 存证模块(pallet-proof)的Runtime API定义，供RPC查询存证信息使用
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_std::vec::Vec;
+
+pub use pallet_proof::{ClaimMetadata, HashAlgorithm, ProvenanceAction, ProvenanceRecord};
+
+sp_api::decl_runtime_apis! {
+	pub trait ProofApi<AccountId, BlockNumber> where
+		AccountId: Codec,
+		BlockNumber: Codec,
+	{
+		/// 查询存证的所有者以及存证时的区块高度
+		fn claim_owner(claim: Vec<u8>) -> Option<(AccountId, BlockNumber)>;
+		/// 查询指定账户拥有的所有存证
+		fn claims_by_owner(account: AccountId) -> Vec<Vec<u8>>;
+		/// 查询存证是否存在
+		fn claim_exists(claim: Vec<u8>) -> bool;
+		/// 查询存证的元数据
+		fn claim_metadata(claim: Vec<u8>) -> Option<ClaimMetadata>;
+		/// 查询存证的溯源记录，按发生顺序返回存证的创建和转移记录，已移除的存证没有记录
+		fn claim_history(claim: Vec<u8>) -> Vec<ProvenanceRecord<AccountId, BlockNumber>>;
+		/// 校验文件的hash值leaf是否包含在已经锚定的Merkle根root中
+		fn verify_merkle_inclusion(
+			root: [u8; 32],
+			leaf: [u8; 32],
+			index: u32,
+			proof: Vec<[u8; 32]>,
+		) -> bool;
+	}
+}
@@ -0,0 +1,173 @@
+//! 存证模块(pallet-proof)的RPC接口，通过一次调用即可查询存证信息
+
+use std::sync::Arc;
+
+use codec::Codec;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::{Bytes, H256};
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+pub use pallet_proof_rpc_runtime_api::{
+	ClaimMetadata, ProofApi as ProofRuntimeApi, ProvenanceAction, ProvenanceRecord,
+};
+
+#[rpc]
+pub trait ProofApi<BlockHash, AccountId, BlockNumber> {
+	/// 查询存证的所有者以及存证时的区块高度
+	#[rpc(name = "proof_claimOwner")]
+	fn claim_owner(
+		&self,
+		claim: Bytes,
+		at: Option<BlockHash>,
+	) -> Result<Option<(AccountId, BlockNumber)>>;
+
+	/// 查询指定账户拥有的所有存证
+	#[rpc(name = "proof_claimsByOwner")]
+	fn claims_by_owner(&self, account: AccountId, at: Option<BlockHash>) -> Result<Vec<Bytes>>;
+
+	/// 查询存证是否存在
+	#[rpc(name = "proof_claimExists")]
+	fn claim_exists(&self, claim: Bytes, at: Option<BlockHash>) -> Result<bool>;
+
+	/// 查询存证的元数据
+	#[rpc(name = "proof_claimMetadata")]
+	fn claim_metadata(&self, claim: Bytes, at: Option<BlockHash>)
+		-> Result<Option<ClaimMetadata>>;
+
+	/// 查询存证的溯源记录，按发生顺序返回存证的创建和转移记录，已移除的存证没有记录
+	#[rpc(name = "proof_claimHistory")]
+	fn claim_history(
+		&self,
+		claim: Bytes,
+		at: Option<BlockHash>,
+	) -> Result<Vec<ProvenanceRecord<AccountId, BlockNumber>>>;
+
+	/// 校验文件的hash值leaf是否包含在已经锚定的Merkle根root中
+	#[rpc(name = "proof_verifyMerkleInclusion")]
+	fn verify_merkle_inclusion(
+		&self,
+		root: H256,
+		leaf: H256,
+		index: u32,
+		proof: Vec<H256>,
+		at: Option<BlockHash>,
+	) -> Result<bool>;
+}
+
+/// 实现存证查询RPC的结构体
+pub struct Proof<C, B> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> Proof<C, B> {
+	/// 通过client创建一个新的存证查询RPC实例
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+/// RPC的错误类型
+pub enum Error {
+	/// 调用runtime api失败
+	RuntimeError,
+}
+
+impl From<Error> for i64 {
+	fn from(e: Error) -> i64 {
+		match e {
+			Error::RuntimeError => 1,
+		}
+	}
+}
+
+impl<C, Block, AccountId, BlockNumber>
+	ProofApi<<Block as BlockT>::Hash, AccountId, BlockNumber> for Proof<C, Block>
+where
+	Block: BlockT,
+	C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: ProofRuntimeApi<Block, AccountId, BlockNumber>,
+	AccountId: Codec,
+	BlockNumber: Codec,
+{
+	fn claim_owner(
+		&self,
+		claim: Bytes,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<(AccountId, BlockNumber)>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.claim_owner(&at, claim.to_vec()).map_err(runtime_error_into_rpc_err)
+	}
+
+	fn claims_by_owner(
+		&self,
+		account: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<Bytes>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.claims_by_owner(&at, account)
+			.map(|claims| claims.into_iter().map(Into::into).collect())
+			.map_err(runtime_error_into_rpc_err)
+	}
+
+	fn claim_exists(&self, claim: Bytes, at: Option<<Block as BlockT>::Hash>) -> Result<bool> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.claim_exists(&at, claim.to_vec()).map_err(runtime_error_into_rpc_err)
+	}
+
+	fn claim_metadata(
+		&self,
+		claim: Bytes,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<ClaimMetadata>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.claim_metadata(&at, claim.to_vec()).map_err(runtime_error_into_rpc_err)
+	}
+
+	fn claim_history(
+		&self,
+		claim: Bytes,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<ProvenanceRecord<AccountId, BlockNumber>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.claim_history(&at, claim.to_vec()).map_err(runtime_error_into_rpc_err)
+	}
+
+	fn verify_merkle_inclusion(
+		&self,
+		root: H256,
+		leaf: H256,
+		index: u32,
+		proof: Vec<H256>,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<bool> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		let proof = proof.into_iter().map(|node| node.to_fixed_bytes()).collect();
+
+		api.verify_merkle_inclusion(&at, root.to_fixed_bytes(), leaf.to_fixed_bytes(), index, proof)
+			.map_err(runtime_error_into_rpc_err)
+	}
+}
+
+/// 将runtime api的错误转换为RPC错误
+fn runtime_error_into_rpc_err(err: impl std::fmt::Debug) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(Error::RuntimeError.into()),
+		message: "Runtime error".into(),
+		data: Some(format!("{:?}", err).into()),
+	}
+}
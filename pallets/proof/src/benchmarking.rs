@@ -0,0 +1,316 @@
+//! 存证模块(pallet-proof)的benchmark
+
+use super::*;
+
+use crate::Pallet as ProofModule;
+use codec::Encode;
+use cumulus_pallet_xcm::Origin as CumulusOrigin;
+use cumulus_primitives_core::ParaId;
+use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite, whitelisted_caller};
+use frame_support::traits::{Currency, Get, Hooks};
+use frame_system::RawOrigin;
+use sp_runtime::traits::{Bounded, Convert, Saturating};
+use sp_std::{vec, vec::Vec};
+
+const SEED: u32 = 0;
+// 跨链查询使用的兄弟平行链
+const SIBLING: u32 = 2000;
+
+/// benchmark中生成可以签名的账户，并用它对链下声明签名
+///
+/// runtime使用的签名类型不同，生成密钥的方式也不同，所以由runtime提供实现
+pub trait BenchmarkHelper<AccountId, Signature> {
+	/// 生成一个可以签名的账户
+	fn signer() -> AccountId;
+	/// 用signer的私钥对payload签名
+	fn sign(signer: &AccountId, payload: &[u8]) -> Signature;
+}
+
+// 给账户充足的余额，用于质押以及购买存证
+fn fund<T: Config>(who: &T::AccountId) {
+	T::Currency::make_free_balance_be(who, BalanceOf::<T>::max_value() / 2u32.into());
+}
+
+// 创建一个最大长度的、有有效期的存证，有效期使存证同时写入过期队列
+fn create_max_claim<T: Config>(owner: &T::AccountId) -> Vec<u8> {
+	let claim = vec![1u8; T::MaxAddend::get()];
+	ProofModule::<T>::create_claim(
+		RawOrigin::Signed(owner.clone()).into(),
+		claim.clone(),
+		Some(100u32.into()),
+	)
+	.expect("claim can be created");
+	claim
+}
+
+// 每个字段都达到最大长度的元数据
+fn max_metadata<T: Config>() -> ClaimMetadata {
+	let max_len = T::MaxMetadataLen::get();
+	ClaimMetadata {
+		hash_algorithm: HashAlgorithm::Blake2_256,
+		content_type: vec![b'a'; max_len],
+		uri: Some(vec![b'u'; max_len]),
+		memo: vec![b'm'; max_len],
+	}
+}
+
+// 最大长度的存证，第一个字节为index，用于生成不同的存证
+fn indexed_claim<T: Config>(index: u32) -> Vec<u8> {
+	let mut claim = index.encode();
+	claim.resize(T::MaxAddend::get(), 0);
+	claim
+}
+
+benchmarks! {
+	where_clause { where <T as frame_system::Config>::Origin: From<CumulusOrigin> }
+
+	create_claim {
+		let l in 1 .. T::MaxAddend::get() as u32;
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let claim = vec![1u8; l as usize];
+	}: _(RawOrigin::Signed(caller.clone()), claim.clone(), Some(100u32.into()))
+	verify {
+		assert_eq!(Proofs::<T>::get(&claim).map(|(owner, _)| owner), Some(caller));
+	}
+
+	create_merkle_claim {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let root = [1u8; 32];
+	}: _(RawOrigin::Signed(caller), root, 1024, Some(100u32.into()))
+	verify {
+		assert_eq!(MerkleLeafCount::<T>::get(root.to_vec()), Some(1024));
+	}
+
+	revoke_claim {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let claim = create_max_claim::<T>(&caller);
+		ProofModule::<T>::set_claim_metadata(
+			RawOrigin::Signed(caller.clone()).into(),
+			claim.clone(),
+			Some(max_metadata::<T>()),
+		)?;
+	}: _(RawOrigin::Signed(caller), claim.clone())
+	verify {
+		assert!(!Proofs::<T>::contains_key(&claim));
+	}
+
+	transfer_claim {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let recipient: T::AccountId = account("recipient", 0, SEED);
+		fund::<T>(&recipient);
+		let claim = create_max_claim::<T>(&caller);
+		// 转移时需要同时清除挂单和操作员
+		ProofModule::<T>::sell_claim(
+			RawOrigin::Signed(caller.clone()).into(),
+			claim.clone(),
+			100u32.into(),
+		)?;
+		ProofModule::<T>::approve_claim_operator(
+			RawOrigin::Signed(caller.clone()).into(),
+			claim.clone(),
+			recipient.clone(),
+		)?;
+	}: _(RawOrigin::Signed(caller), claim.clone(), recipient.clone())
+	verify {
+		assert_eq!(Proofs::<T>::get(&claim).map(|(owner, _)| owner), Some(recipient));
+	}
+
+	sell_claim {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let claim = create_max_claim::<T>(&caller);
+	}: _(RawOrigin::Signed(caller), claim.clone(), 100u32.into())
+	verify {
+		assert!(ClaimListings::<T>::contains_key(&claim));
+	}
+
+	cancel_claim_sale {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let claim = create_max_claim::<T>(&caller);
+		ProofModule::<T>::sell_claim(
+			RawOrigin::Signed(caller.clone()).into(),
+			claim.clone(),
+			100u32.into(),
+		)?;
+	}: _(RawOrigin::Signed(caller), claim.clone())
+	verify {
+		assert!(!ClaimListings::<T>::contains_key(&claim));
+	}
+
+	buy_claim {
+		let seller: T::AccountId = account("seller", 0, SEED);
+		fund::<T>(&seller);
+		let claim = create_max_claim::<T>(&seller);
+		ProofModule::<T>::sell_claim(
+			RawOrigin::Signed(seller).into(),
+			claim.clone(),
+			100u32.into(),
+		)?;
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+	}: _(RawOrigin::Signed(caller.clone()), claim.clone(), 100u32.into())
+	verify {
+		assert_eq!(Proofs::<T>::get(&claim).map(|(owner, _)| owner), Some(caller));
+	}
+
+	approve_claim_operator {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let claim = create_max_claim::<T>(&caller);
+		// 授权到只剩一个空位，新的授权需要检查所有已有的操作员
+		for i in 1 .. T::MaxClaimOperators::get() {
+			ProofModule::<T>::approve_claim_operator(
+				RawOrigin::Signed(caller.clone()).into(),
+				claim.clone(),
+				account("operator", i, SEED),
+			)?;
+		}
+		let operator: T::AccountId = account("operator", 0, SEED);
+	}: _(RawOrigin::Signed(caller), claim.clone(), operator.clone())
+	verify {
+		assert!(ClaimOperators::<T>::get(&claim).contains(&operator));
+	}
+
+	cancel_claim_operator {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let claim = create_max_claim::<T>(&caller);
+		for i in 0 .. T::MaxClaimOperators::get() {
+			ProofModule::<T>::approve_claim_operator(
+				RawOrigin::Signed(caller.clone()).into(),
+				claim.clone(),
+				account("operator", i, SEED),
+			)?;
+		}
+		// 取消最后一个授权，需要查找所有的操作员
+		let operator: T::AccountId =
+			account("operator", T::MaxClaimOperators::get().saturating_sub(1), SEED);
+	}: _(RawOrigin::Signed(caller), claim.clone(), operator.clone())
+	verify {
+		assert!(!ClaimOperators::<T>::get(&claim).contains(&operator));
+	}
+
+	set_operator_for_all {
+		let caller: T::AccountId = whitelisted_caller();
+		let operator: T::AccountId = account("operator", 0, SEED);
+	}: _(RawOrigin::Signed(caller.clone()), operator.clone(), true)
+	verify {
+		assert!(OwnerOperators::<T>::contains_key(&caller, &operator));
+	}
+
+	set_claim_metadata {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let claim = create_max_claim::<T>(&caller);
+	}: _(RawOrigin::Signed(caller), claim.clone(), Some(max_metadata::<T>()))
+	verify {
+		assert!(Metadata::<T>::contains_key(&claim));
+	}
+
+	create_claim_with_signature {
+		let l in 1 .. T::MaxAddend::get() as u32;
+		let owner = T::BenchmarkHelper::signer();
+		fund::<T>(&owner);
+		let attestation = ClaimAttestation {
+			owner: owner.clone(),
+			claim: vec![1u8; l as usize],
+			nonce: 0,
+			deadline: 100u32.into(),
+		};
+		let payload = ProofModule::<T>::attestation_payload(&attestation);
+		let signature = T::BenchmarkHelper::sign(&owner, &payload);
+	}: _(RawOrigin::None, attestation.clone(), signature)
+	verify {
+		assert_eq!(Proofs::<T>::get(&attestation.claim).map(|(owner, _)| owner), Some(owner));
+	}
+
+	request_claim_verification {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let claim = vec![1u8; T::MaxAddend::get()];
+	}: _(RawOrigin::Signed(caller), ParaId::from(SIBLING), claim)
+	verify {
+		assert!(PendingVerifications::<T>::contains_key(0));
+	}
+
+	// 存证存在时需要把所有者和区块高度发回给对方
+	verify_claim {
+		let owner: T::AccountId = account("owner", 0, SEED);
+		fund::<T>(&owner);
+		let claim = create_max_claim::<T>(&owner);
+		fund::<T>(&T::SiblingAccount::convert(ParaId::from(SIBLING)));
+		let origin: <T as frame_system::Config>::Origin =
+			CumulusOrigin::SiblingParachain(ParaId::from(SIBLING)).into();
+	}: _(origin, 0, claim.clone())
+	verify {
+		assert!(Proofs::<T>::contains_key(&claim));
+	}
+
+	claim_verified {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let claim = vec![1u8; T::MaxAddend::get()];
+		ProofModule::<T>::request_claim_verification(
+			RawOrigin::Signed(caller.clone()).into(),
+			ParaId::from(SIBLING),
+			claim.clone(),
+		)?;
+		let origin: <T as frame_system::Config>::Origin =
+			CumulusOrigin::SiblingParachain(ParaId::from(SIBLING)).into();
+	}: _(origin, 0, claim, Some((caller, 1u32.into())))
+	verify {
+		assert!(!PendingVerifications::<T>::contains_key(0));
+	}
+
+	cancel_verification {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		ProofModule::<T>::request_claim_verification(
+			RawOrigin::Signed(caller.clone()).into(),
+			ParaId::from(SIBLING),
+			vec![1u8; T::MaxAddend::get()],
+		)?;
+		let now = frame_system::Pallet::<T>::block_number();
+		frame_system::Pallet::<T>::set_block_number(
+			now.saturating_add(T::VerificationTimeout::get()).saturating_add(1u32.into()),
+		);
+	}: _(RawOrigin::Signed(caller), 0)
+	verify {
+		assert!(!PendingVerifications::<T>::contains_key(0));
+	}
+
+	// 在同一个区块过期n个存证，每个存证属于不同的所有者并设置了元数据
+	on_initialize {
+		let n in 0 .. T::MaxExpiringPerBlock::get();
+		for i in 0 .. n {
+			let owner: T::AccountId = account("owner", i, SEED);
+			fund::<T>(&owner);
+			let claim = indexed_claim::<T>(i);
+			ProofModule::<T>::create_claim(
+				RawOrigin::Signed(owner.clone()).into(),
+				claim.clone(),
+				Some(100u32.into()),
+			)?;
+			ProofModule::<T>::set_claim_metadata(
+				RawOrigin::Signed(owner).into(),
+				claim,
+				Some(max_metadata::<T>()),
+			)?;
+		}
+		let expires_at = frame_system::Pallet::<T>::block_number().saturating_add(100u32.into());
+		assert_eq!(ExpiringClaims::<T>::get(expires_at).len() as u32, n);
+	}: {
+		ProofModule::<T>::on_initialize(expires_at);
+	}
+	verify {
+		assert!(!ExpiringClaims::<T>::contains_key(expires_at));
+		assert!(!Proofs::<T>::contains_key(indexed_claim::<T>(0)));
+	}
+}
+
+impl_benchmark_test_suite!(ProofModule, crate::mock::new_test_ext(), crate::mock::Test);
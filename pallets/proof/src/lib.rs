@@ -5,23 +5,114 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
+#[cfg(feature = "runtime-benchmarks")]
+pub mod benchmarking;
+
+pub mod merkle;
+pub mod migrations;
+mod types;
+pub mod weights;
+
 pub use pallet::*;
+pub use weights::WeightInfo;
 
 #[frame_support::pallet]
 pub mod pallet {
-	use frame_support::{dispatch::DispatchResultWithPostInfo, pallet_prelude::*};
-	use frame_system::pallet_prelude::*;
-	use sp_std::vec::Vec;
+	use frame_support::{
+		dispatch::{DispatchResult, DispatchResultWithPostInfo},
+		pallet_prelude::*,
+		traits::{
+			BalanceStatus, Currency, ExistenceRequirement::KeepAlive, ReservableCurrency, StorageVersion,
+			WithdrawReasons,
+		},
+		transactional,
+	};
+	use cumulus_pallet_xcm::{ensure_sibling_para, Origin as CumulusOrigin};
+	use cumulus_primitives_core::ParaId;
+	use frame_system::{pallet_prelude::*, Config as SystemConfig};
+	use sp_runtime::{
+		traits::{Convert, IdentifyAccount, Saturating, Verify, Zero},
+		SaturatedConversion,
+	};
+	use sp_std::{vec, vec::Vec};
+	use xcm::latest::prelude::*;
+
+	pub use crate::types::{
+		ClaimAttestation, ClaimMetadata, HashAlgorithm, PendingVerification, ProvenanceAction,
+		ProvenanceRecord,
+	};
+	use crate::{merkle, WeightInfo};
+
+	pub type BalanceOf<T> =
+		<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 
 	#[pallet::config]
 	pub trait Config: frame_system::Config {
 		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+		// 代币的关联类型，用于存证的买卖以及创建存证时的质押
+		type Currency: Currency<Self::AccountId> + ReservableCurrency<Self::AccountId>;
+		// 创建存证需要质押的基础金额
+		type ClaimDepositBase: Get<BalanceOf<Self>>;
+		// 存证每个字节需要额外质押的金额，存证越长占用的链上存储越多，质押也越多
+		type ClaimDepositPerByte: Get<BalanceOf<Self>>;
 		// 设置长度上限（这里主要是为了设置存证内容的hash）,因为链上的存证内容不能无限大，否则容易受到攻击
 		type MaxAddend: Get<usize>;
+		// 每个账户最多可以拥有的存证数量，用于限制账户存证索引的大小
+		type MaxClaimsPerOwner: Get<u32>;
+		// 同一个区块中最多可以过期的存证数量，用于限制on_initialize中每次清理的数量
+		type MaxExpiringPerBlock: Get<u32>;
+		// 存证元数据中content_type、uri、memo每个字段的最大长度
+		type MaxMetadataLen: Get<usize>;
+		// 每个存证最多可以授权的操作员数量
+		type MaxClaimOperators: Get<u32>;
+		// 每个存证最多保存的溯源记录数量，超过后丢弃创建记录之后最早的记录
+		type MaxHistoryLen: Get<u32>;
+
+		// 用于识别跨链消息的来源(兄弟平行链)
+		type Origin: From<<Self as SystemConfig>::Origin>
+			+ Into<Result<CumulusOrigin, <Self as Config>::Origin>>;
+		// 跨链消息中调用的Call类型，这里假设兄弟平行链使用相同的Call类型
+		type Call: From<Call<Self>> + Encode;
+		// 发送跨链消息的模块
+		type XcmSender: SendXcm;
+		// 跨链查询以及返回查询结果时，Transact最多可以使用的weight
+		type XcmWeightAtMost: Get<u64>;
+		// 发起跨链查询需要质押的金额，收到结果或查询超时后退回
+		// 兄弟平行链查询本链的存证时，同样从它在本链的主权账户中收取这个金额作为费用
+		type VerificationDeposit: Get<BalanceOf<Self>>;
+		// 兄弟平行链在本链的主权账户
+		type SiblingAccount: Convert<ParaId, Self::AccountId>;
+		// 跨链查询的超时区块数，超时后任何账户都可以清除该查询
+		type VerificationTimeout: Get<Self::BlockNumber>;
+		// 各个交易的weight，目前是人工估算的数值，见weights.rs
+		type WeightInfo: WeightInfo;
+
+		// 链下声明的签名类型，例如MultiSignature(支持sr25519/ed25519)
+		type Signature: Verify<Signer = Self::Signer> + Parameter;
+		// 签名者的公钥类型，可以转换为AccountId
+		type Signer: IdentifyAccount<AccountId = Self::AccountId>;
+		// 通过签名声明创建存证的不签名交易在交易池中的优先级
+		type UnsignedPriority: Get<TransactionPriority>;
+
+		// benchmark中生成签名账户以及链下声明的签名
+		#[cfg(feature = "runtime-benchmarks")]
+		type BenchmarkHelper: crate::benchmarking::BenchmarkHelper<Self::AccountId, Self::Signature>;
 	}
 
+	// 不签名交易校验失败时的自定义错误码
+	const INVALID_CLAIM_LENGTH: u8 = 1;
+	const CLAIM_ALREADY_EXIST: u8 = 2;
+	const TOO_MANY_CLAIMS: u8 = 3;
+
+	// 链下声明签名内容的前缀，与创世区块hash一起防止声明在其他链上被重放
+	pub const ATTESTATION_CONTEXT: &[u8] = b"pallet-proof/claim-attestation";
+
+	// 当前的存储版本，版本1引入了账户的存证索引ClaimsOf
+	pub(crate) const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	#[pallet::without_storage_info]
 	pub struct Pallet<T>(_);
 
@@ -30,6 +121,137 @@ pub mod pallet {
 	pub type Proofs<T: Config> =
 		StorageMap<_, Blake2_128Concat, Vec<u8>, (T::AccountId, T::BlockNumber)>;
 
+	// 账户的存证索引，记录每个账户拥有的所有存证，需要与Proofs保持一致
+	#[pallet::storage]
+	#[pallet::getter(fn claims_of)]
+	pub type ClaimsOf<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		BoundedVec<Vec<u8>, T::MaxClaimsPerOwner>,
+		ValueQuery,
+	>;
+
+	// 存证交易市场，存证的hash值为key，挂单的价格为value
+	#[pallet::storage]
+	#[pallet::getter(fn claim_listings)]
+	pub type ClaimListings<T: Config> = StorageMap<_, Blake2_128Concat, Vec<u8>, BalanceOf<T>>;
+
+	// 每个存证质押的金额，存证转移或注销时按这个金额退回质押
+	// 创世区块中的存证没有质押
+	#[pallet::storage]
+	#[pallet::getter(fn claim_deposits)]
+	pub type ClaimDeposits<T: Config> =
+		StorageMap<_, Blake2_128Concat, Vec<u8>, BalanceOf<T>, ValueQuery>;
+
+	// 有有效期的存证的过期区块高度
+	#[pallet::storage]
+	#[pallet::getter(fn claim_expiry)]
+	pub type ClaimExpiry<T: Config> = StorageMap<_, Blake2_128Concat, Vec<u8>, T::BlockNumber>;
+
+	// 过期队列，区块高度为key，在该区块过期的存证为value，在on_initialize中清理
+	#[pallet::storage]
+	#[pallet::getter(fn expiring_claims)]
+	pub type ExpiringClaims<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::BlockNumber,
+		BoundedVec<Vec<u8>, T::MaxExpiringPerBlock>,
+		ValueQuery,
+	>;
+
+	// 存证的元数据，只有存证的所有者可以修改
+	#[pallet::storage]
+	#[pallet::getter(fn claim_metadata)]
+	pub type Metadata<T: Config> = StorageMap<_, Blake2_128Concat, Vec<u8>, ClaimMetadata>;
+
+	// 批量存证的Merkle根对应的叶子数量
+	#[pallet::storage]
+	#[pallet::getter(fn merkle_leaf_count)]
+	pub type MerkleLeafCount<T: Config> = StorageMap<_, Blake2_128Concat, Vec<u8>, u32>;
+
+	// 存证的操作员，所有者授权的操作员可以代替所有者注销或转移该存证
+	// 存证的所有权变化或存证被移除后，授权自动失效
+	#[pallet::storage]
+	#[pallet::getter(fn claim_operators)]
+	pub type ClaimOperators<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		Vec<u8>,
+		BoundedVec<T::AccountId, T::MaxClaimOperators>,
+		ValueQuery,
+	>;
+
+	// 账户的操作员，(所有者, 操作员)为key，操作员可以代替所有者注销或转移所有者的全部存证
+	#[pallet::storage]
+	pub type OwnerOperators<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		T::AccountId,
+		(),
+	>;
+
+	// 存证的溯源记录，按发生顺序记录存证的创建、每一次转移以及最终的注销或过期，创建记录始终保留
+	// 溯源记录只追加不删除，存证被注销或过期之后仍然可以查询
+	#[pallet::storage]
+	#[pallet::getter(fn claim_history)]
+	pub type ClaimHistory<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		Vec<u8>,
+		BoundedVec<ProvenanceRecord<T::AccountId, T::BlockNumber>, T::MaxHistoryLen>,
+		ValueQuery,
+	>;
+
+	// 下一个跨链查询的编号
+	#[pallet::storage]
+	pub type NextVerificationId<T: Config> = StorageValue<_, u64, ValueQuery>;
+
+	// 已经发出、还没有收到结果的跨链查询，每个查询都有质押和超时区块
+	#[pallet::storage]
+	pub type PendingVerifications<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		u64,
+		PendingVerification<T::AccountId, BalanceOf<T>, T::BlockNumber>,
+	>;
+
+	// 每个账户下一个链下声明的序号，每使用一个声明加1
+	#[pallet::storage]
+	#[pallet::getter(fn attestation_nonce)]
+	pub type AttestationNonces<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, u64, ValueQuery>;
+
+	// 创世配置，可以在链启动时预置一些存证，格式为(所有者, 存证的hash值)
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		pub claims: Vec<(T::AccountId, Vec<u8>)>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			Self { claims: Vec::new() }
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+			for (owner, claim) in &self.claims {
+				assert!(claim.len() <= T::MaxAddend::get(), "genesis claim exceeds MaxAddend");
+				assert!(!Proofs::<T>::contains_key(claim), "duplicate claim in genesis");
+				// 创世区块中的存证，区块高度记为0
+				Proofs::<T>::insert(claim, (owner.clone(), T::BlockNumber::zero()));
+				Pallet::<T>::add_claim_to_owner(owner, claim)
+					.expect("genesis claims exceed MaxClaimsPerOwner");
+				Pallet::<T>::record_history(claim, ProvenanceAction::Created(owner.clone()));
+			}
+		}
+	}
+
 	// 定义Event类型
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
@@ -37,6 +259,36 @@ pub mod pallet {
 		ClaimCreated(T::AccountId, Vec<u8>),
 		ClaimRevoked(T::AccountId, Vec<u8>),
 		ClaimTransfered(T::AccountId, Vec<u8>),
+		// 所有者，存证，挂单价格
+		ClaimListed(T::AccountId, Vec<u8>, BalanceOf<T>),
+		// 所有者，存证
+		ClaimListingCancelled(T::AccountId, Vec<u8>),
+		// 原所有者，买家，存证，成交价格
+		ClaimSold(T::AccountId, T::AccountId, Vec<u8>, BalanceOf<T>),
+		// 所有者，已经过期并被移除的存证
+		ClaimExpired(T::AccountId, Vec<u8>),
+		// 所有者，元数据被设置或清除的存证
+		ClaimMetadataUpdated(T::AccountId, Vec<u8>),
+		// 所有者，锚定的Merkle根，叶子数量
+		MerkleClaimCreated(T::AccountId, Vec<u8>, u32),
+		// 所有者，存证，被授权的操作员
+		ClaimOperatorApproved(T::AccountId, Vec<u8>, T::AccountId),
+		// 所有者，存证，被取消授权的操作员
+		ClaimOperatorCancelled(T::AccountId, Vec<u8>, T::AccountId),
+		// 所有者，操作员，是否授权操作所有者的全部存证
+		OperatorForAllSet(T::AccountId, T::AccountId, bool),
+		// 发起查询的账户，目标平行链，查询编号，存证
+		VerificationRequested(T::AccountId, ParaId, u64, Vec<u8>),
+		// 兄弟平行链查询了本链的存证，查询结果已经发回：平行链，查询编号，存证，(所有者, 存证时的区块高度)
+		ClaimVerified(ParaId, u64, Vec<u8>, Option<(T::AccountId, T::BlockNumber)>),
+		// 查询结果发送失败
+		ErrorSendingVerification(SendError, ParaId, u64),
+		// 收到了兄弟平行链返回的查询结果：平行链，查询编号，存证，(所有者, 存证时的区块高度)
+		VerificationReceived(ParaId, u64, Vec<u8>, Option<(T::AccountId, T::BlockNumber)>),
+		// 收到了不是本链发出的查询的结果
+		UnknownVerification(ParaId, u64),
+		// 查询超时被清除，质押已退回：发起查询的账户，目标平行链，查询编号
+		VerificationTimedOut(T::AccountId, ParaId, u64),
 	}
 
 	#[pallet::error]
@@ -45,18 +297,81 @@ pub mod pallet {
 		ClaimNotExist,
 		NotClaimOwner,
 		ClaimOutLength,
+		// 账户拥有的存证数量超过了上限
+		TooManyClaims,
+		// 存证没有在交易市场挂单
+		ClaimNotForSale,
+		// 不能购买自己的存证
+		BuyOwnClaim,
+		// 挂单价格高于买家愿意支付的最高价格
+		PriceTooHigh,
+		// 没有足够的余额用于质押
+		NoSufficientBalance,
+		// 存证的有效期不能为0
+		InvalidTtl,
+		// 同一个区块过期的存证数量超过了上限
+		TooManyExpiringClaims,
+		// 元数据的字段超过了长度上限
+		MetadataOutLength,
+		// Merkle树的叶子数量不能为0
+		InvalidLeafCount,
+		// 存证授权的操作员数量超过了上限
+		TooManyOperators,
+		// 该账户不是存证的操作员
+		NotClaimOperator,
+		// 发送跨链消息失败
+		XcmSendError,
+		// 链下声明的签名无效
+		BadSignature,
+		// 链下声明已经超过了截止区块
+		AttestationExpired,
+		// 链下声明的序号与链上记录的不一致
+		InvalidAttestationNonce,
+		// 跨链查询不存在或者已经收到结果
+		VerificationNotFound,
+		// 跨链查询还没有超时
+		VerificationNotExpired,
+		// 兄弟平行链的主权账户余额不足，无法支付查询费用
+		SiblingCannotPay,
 	}
 
 	#[pallet::hooks]
-	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		// 移除在当前区块过期的存证，每个区块最多处理MaxExpiringPerBlock个
+		fn on_initialize(n: T::BlockNumber) -> Weight {
+			let expiring = ExpiringClaims::<T>::take(n);
+			let count = expiring.len() as u32;
+
+			for claim in expiring.into_iter() {
+				if let Some((owner, _)) = Proofs::<T>::get(&claim) {
+					Self::record_history(&claim, ProvenanceAction::Expired { at: n });
+					Self::do_remove_claim(&claim, &owner);
+					Self::deposit_event(Event::ClaimExpired(owner, claim));
+				}
+			}
+
+			T::WeightInfo::on_initialize(count)
+		}
+
+		fn on_runtime_upgrade() -> Weight {
+			crate::migrations::v1::migrate::<T>()
+		}
+
+		fn integrity_test() {
+			// 溯源记录至少要容纳创建记录和最后的注销或过期记录
+			assert!(T::MaxHistoryLen::get() >= 2, "MaxHistoryLen must be at least 2");
+		}
+	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 		// 创建存证
-		#[pallet::weight(0)] // 可以通过计算来获取weight合理的值，这里是简单起见，设置为0
+		#[pallet::weight(T::WeightInfo::create_claim(claim.len() as u32))]
+		#[transactional]
 		pub fn create_claim(
-			origin: OriginFor<T>, // 交易的发送方
-			claim: Vec<u8>,       // 存证的hash值
+			origin: OriginFor<T>,        // 交易的发送方
+			claim: Vec<u8>,              // 存证的hash值
+			ttl: Option<T::BlockNumber>, // 存证的有效期（区块数），None表示永久有效
 		) -> DispatchResultWithPostInfo {
 			// 校验并获取发送方的AccountId
 			let sender = ensure_signed(origin)?;
@@ -64,21 +379,39 @@ pub mod pallet {
 			// 限制claim的长度
 			ensure!(claim.len().le(&(T::MaxAddend::get())), Error::<T>::ClaimOutLength);
 
-			// 判断当前的存储单元中，是否已经存在了这样的存证记录，如果存在了，那就报已经存在的错误
-			ensure!(!Proofs::<T>::contains_key(&claim), Error::<T>::ProofAlreadyExist);
-
-			// 向链上存储单元中插入一条存证记录
-			Proofs::<T>::insert(
-				&claim,                                                      /* key为 存证的hash值 */
-				(sender.clone(), frame_system::Pallet::<T>::block_number()), /* value为 一个元组，其中包含(AccountId,BlockNumber) */
-			);
+			Self::do_create_claim(&sender, &claim, ttl)?;
 
 			Self::deposit_event(Event::ClaimCreated(sender, claim));
 			Ok(().into())
 		}
 
+		// 批量存证：将大量文件的hash值构造成Merkle树，只在链上锚定Merkle根和叶子数量
+		// 锚定的Merkle根作为一个普通的存证，可以注销、转移和出售
+		// 文件是否包含在某个Merkle根中可以通过verify_merkle_inclusion校验
+		#[pallet::weight(T::WeightInfo::create_merkle_claim())]
+		#[transactional]
+		pub fn create_merkle_claim(
+			origin: OriginFor<T>,
+			root: [u8; 32],              // Merkle根
+			leaf_count: u32,             // Merkle树的叶子数量，即文件数量
+			ttl: Option<T::BlockNumber>, // 存证的有效期（区块数），None表示永久有效
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(leaf_count > 0, Error::<T>::InvalidLeafCount);
+
+			// Merkle根和普通的存证一样受MaxAddend的限制
+			let claim = root.to_vec();
+			ensure!(claim.len() <= T::MaxAddend::get(), Error::<T>::ClaimOutLength);
+			Self::do_create_claim(&sender, &claim, ttl)?;
+			MerkleLeafCount::<T>::insert(&claim, leaf_count);
+
+			Self::deposit_event(Event::MerkleClaimCreated(sender, claim, leaf_count));
+			Ok(().into())
+		}
+
 		// 注销存证
-		#[pallet::weight(0)]
+		#[pallet::weight(T::WeightInfo::revoke_claim())]
 		pub fn revoke_claim(
 			origin: OriginFor<T>, // 交易的发送方
 			claim: Vec<u8>,       // 存证的hash值
@@ -88,8 +421,16 @@ pub mod pallet {
 			// 如果Proofs中没有，就报错，如果有，就用?将其中的值取出来
 			let (owner, _) = Proofs::<T>::get(&claim).ok_or(Error::<T>::ClaimNotExist)?;
 
-			ensure!(owner == sender, Error::<T>::NotClaimOwner);
-			Proofs::<T>::remove(&claim);
+			// 所有者或者所有者授权的操作员才能注销存证
+			ensure!(Self::is_owner_or_operator(&sender, &owner, &claim), Error::<T>::NotClaimOwner);
+			Self::record_history(
+				&claim,
+				ProvenanceAction::Revoked {
+					by: sender.clone(),
+					at: frame_system::Pallet::<T>::block_number(),
+				},
+			);
+			Self::do_remove_claim(&claim, &owner);
 
 			Self::deposit_event(Event::ClaimRevoked(sender, claim));
 
@@ -97,7 +438,8 @@ pub mod pallet {
 		}
 
 		// 转移存证
-		#[pallet::weight(0)]
+		#[pallet::weight(T::WeightInfo::transfer_claim())]
+		#[transactional]
 		pub fn transfer_claim(
 			origin: OriginFor<T>,
 			claim: Vec<u8>,
@@ -109,24 +451,615 @@ pub mod pallet {
 			let (owner, block_number) =
 				Proofs::<T>::get(&claim).ok_or(Error::<T>::ClaimNotExist)?;
 
-			// 确定当前的存证是否是属于当前的发送者
-			ensure!(owner == sender, Error::<T>::NotClaimOwner);
+			// 确定当前的发送者是存证的所有者或者所有者授权的操作员
+			ensure!(Self::is_owner_or_operator(&sender, &owner, &claim), Error::<T>::NotClaimOwner);
 
-			Proofs::<T>::remove(&claim);
-			Proofs::<T>::insert(&claim, (rec_account, block_number));
+			Self::do_transfer_claim(&claim, &owner, block_number, rec_account)?;
 
 			Self::deposit_event(Event::ClaimTransfered(sender, claim));
 
 			Ok(().into())
 		}
 
-		// 销售存证
-		// #[pallet::weight(0)]
-		// pub fn sell_claim(
-		// 	origin: OriginFor<T>, // 交易的发送方
-		// 	claim: Vec<u8>, // 存证
-		// ) -> DispatchResultWithPostInfo{
+		// 销售存证，将存证以指定的价格挂单到交易市场，重复挂单会更新价格
+		#[pallet::weight(T::WeightInfo::sell_claim())]
+		pub fn sell_claim(
+			origin: OriginFor<T>, // 交易的发送方
+			claim: Vec<u8>,       // 存证
+			price: BalanceOf<T>,  // 挂单价格
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+
+			let (owner, _) = Proofs::<T>::get(&claim).ok_or(Error::<T>::ClaimNotExist)?;
+			ensure!(owner == sender, Error::<T>::NotClaimOwner);
+
+			ClaimListings::<T>::insert(&claim, price);
+
+			Self::deposit_event(Event::ClaimListed(sender, claim, price));
+			Ok(().into())
+		}
+
+		// 取消存证的挂单
+		#[pallet::weight(T::WeightInfo::cancel_claim_sale())]
+		pub fn cancel_claim_sale(
+			origin: OriginFor<T>,
+			claim: Vec<u8>,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+
+			let (owner, _) = Proofs::<T>::get(&claim).ok_or(Error::<T>::ClaimNotExist)?;
+			ensure!(owner == sender, Error::<T>::NotClaimOwner);
+			ensure!(ClaimListings::<T>::contains_key(&claim), Error::<T>::ClaimNotForSale);
+
+			ClaimListings::<T>::remove(&claim);
+
+			Self::deposit_event(Event::ClaimListingCancelled(sender, claim));
+			Ok(().into())
+		}
+
+		// 购买存证，买家支付挂单价格给所有者，存证的所有权转移给买家
+		// max_price为买家愿意支付的最高价格，防止所有者在交易上链前抬高价格
+		#[pallet::weight(T::WeightInfo::buy_claim())]
+		#[transactional]
+		pub fn buy_claim(
+			origin: OriginFor<T>,
+			claim: Vec<u8>,
+			max_price: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let buyer = ensure_signed(origin)?;
+
+			let price = ClaimListings::<T>::get(&claim).ok_or(Error::<T>::ClaimNotForSale)?;
+			let (owner, block_number) =
+				Proofs::<T>::get(&claim).ok_or(Error::<T>::ClaimNotExist)?;
+
+			ensure!(buyer != owner, Error::<T>::BuyOwnClaim);
+			ensure!(price <= max_price, Error::<T>::PriceTooHigh);
+
+			// 买家支付挂单价格给所有者，然后复用转移存证的逻辑转移所有权（同时会移除挂单）
+			T::Currency::transfer(&buyer, &owner, price, KeepAlive)?;
+			Self::do_transfer_claim(&claim, &owner, block_number, buyer.clone())?;
+
+			Self::deposit_event(Event::ClaimSold(owner, buyer, claim, price));
+			Ok(().into())
+		}
+
+		// 授权operator代替所有者注销或转移指定的存证
+		#[pallet::weight(T::WeightInfo::approve_claim_operator())]
+		pub fn approve_claim_operator(
+			origin: OriginFor<T>,
+			claim: Vec<u8>,
+			operator: T::AccountId,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+
+			let (owner, _) = Proofs::<T>::get(&claim).ok_or(Error::<T>::ClaimNotExist)?;
+			ensure!(owner == sender, Error::<T>::NotClaimOwner);
+
+			// 重复授权不会重复记录
+			ClaimOperators::<T>::try_mutate(&claim, |operators| {
+				if operators.contains(&operator) {
+					return Ok(())
+				}
+				operators.try_push(operator.clone()).map_err(|_| Error::<T>::TooManyOperators)
+			})?;
+
+			Self::deposit_event(Event::ClaimOperatorApproved(sender, claim, operator));
+			Ok(().into())
+		}
+
+		// 取消operator对指定存证的授权
+		#[pallet::weight(T::WeightInfo::cancel_claim_operator())]
+		pub fn cancel_claim_operator(
+			origin: OriginFor<T>,
+			claim: Vec<u8>,
+			operator: T::AccountId,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
 
-		// }
+			let (owner, _) = Proofs::<T>::get(&claim).ok_or(Error::<T>::ClaimNotExist)?;
+			ensure!(owner == sender, Error::<T>::NotClaimOwner);
+
+			ClaimOperators::<T>::try_mutate_exists(&claim, |maybe_operators| {
+				let operators = maybe_operators.as_mut().ok_or(Error::<T>::NotClaimOperator)?;
+				let index = operators
+					.iter()
+					.position(|o| o == &operator)
+					.ok_or(Error::<T>::NotClaimOperator)?;
+				operators.remove(index);
+				if operators.is_empty() {
+					*maybe_operators = None;
+				}
+				Ok::<(), Error<T>>(())
+			})?;
+
+			Self::deposit_event(Event::ClaimOperatorCancelled(sender, claim, operator));
+			Ok(().into())
+		}
+
+		// 授权或取消授权operator代替调用者注销或转移其全部存证
+		#[pallet::weight(T::WeightInfo::set_operator_for_all())]
+		pub fn set_operator_for_all(
+			origin: OriginFor<T>,
+			operator: T::AccountId,
+			approved: bool,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+
+			if approved {
+				OwnerOperators::<T>::insert(&sender, &operator, ());
+			} else {
+				OwnerOperators::<T>::remove(&sender, &operator);
+			}
+
+			Self::deposit_event(Event::OperatorForAllSet(sender, operator, approved));
+			Ok(().into())
+		}
+
+		// 设置存证的元数据，metadata为None时清除元数据
+		// 元数据同样占用链上存储，质押会按元数据的长度相应地增加或减少
+		#[pallet::weight(T::WeightInfo::set_claim_metadata())]
+		#[transactional]
+		pub fn set_claim_metadata(
+			origin: OriginFor<T>,
+			claim: Vec<u8>,
+			metadata: Option<ClaimMetadata>,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+
+			let (owner, _) = Proofs::<T>::get(&claim).ok_or(Error::<T>::ClaimNotExist)?;
+			ensure!(owner == sender, Error::<T>::NotClaimOwner);
+
+			if let Some(metadata) = &metadata {
+				ensure!(
+					metadata.within_length(T::MaxMetadataLen::get()),
+					Error::<T>::MetadataOutLength
+				);
+			}
+
+			// 按新的元数据调整质押金额
+			let old_deposit = ClaimDeposits::<T>::get(&claim);
+			let new_deposit = Self::claim_deposit(&claim, metadata.as_ref());
+			if new_deposit > old_deposit {
+				T::Currency::reserve(&owner, new_deposit - old_deposit)
+					.map_err(|_| Error::<T>::NoSufficientBalance)?;
+			} else {
+				T::Currency::unreserve(&owner, old_deposit - new_deposit);
+			}
+			ClaimDeposits::<T>::insert(&claim, new_deposit);
+
+			match metadata {
+				Some(metadata) => Metadata::<T>::insert(&claim, metadata),
+				None => Metadata::<T>::remove(&claim),
+			}
+
+			Self::deposit_event(Event::ClaimMetadataUpdated(sender, claim));
+			Ok(().into())
+		}
+
+		// 提交所有者在链下签名的存证声明，由中继者以不签名交易提交，所有者不需要支付手续费
+		// 创建存证的质押仍然从所有者的账户中扣除
+		#[pallet::weight(T::WeightInfo::create_claim_with_signature(attestation.claim.len() as u32))]
+		#[transactional]
+		pub fn create_claim_with_signature(
+			origin: OriginFor<T>,
+			attestation: ClaimAttestation<T::AccountId, T::BlockNumber>,
+			signature: T::Signature,
+		) -> DispatchResultWithPostInfo {
+			ensure_none(origin)?;
+
+			Self::check_attestation(&attestation)?;
+			ensure!(
+				signature.verify(&Self::attestation_payload(&attestation)[..], &attestation.owner),
+				Error::<T>::BadSignature
+			);
+
+			let ClaimAttestation { owner, claim, nonce, .. } = attestation;
+			Self::do_create_claim(&owner, &claim, None)?;
+			AttestationNonces::<T>::insert(&owner, nonce.saturating_add(1));
+
+			Self::deposit_event(Event::ClaimCreated(owner, claim));
+			Ok(().into())
+		}
+
+		// 向兄弟平行链para发起跨链查询，查询存证claim在该链上的所有者和区块高度
+		// 查询结果由对方通过claim_verified返回
+		// 发起查询需要质押VerificationDeposit，收到结果或者查询超时后退回
+		#[pallet::weight(T::WeightInfo::request_claim_verification())]
+		#[transactional]
+		pub fn request_claim_verification(
+			origin: OriginFor<T>,
+			para: ParaId,
+			claim: Vec<u8>,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(claim.len() <= T::MaxAddend::get(), Error::<T>::ClaimOutLength);
+			let deposit = T::VerificationDeposit::get();
+			T::Currency::reserve(&sender, deposit).map_err(|_| Error::<T>::NoSufficientBalance)?;
+
+			let query_id = NextVerificationId::<T>::get();
+			let message = Xcm(vec![Transact {
+				origin_type: OriginKind::Native,
+				require_weight_at_most: T::XcmWeightAtMost::get(),
+				call: <T as Config>::Call::from(Call::<T>::verify_claim {
+					query_id,
+					claim: claim.clone(),
+				})
+				.encode()
+				.into(),
+			}]);
+			T::XcmSender::send_xcm((1, Junction::Parachain(para.into())), message)
+				.map_err(|_| Error::<T>::XcmSendError)?;
+
+			NextVerificationId::<T>::put(query_id.wrapping_add(1));
+			let expires_at =
+				frame_system::Pallet::<T>::block_number().saturating_add(T::VerificationTimeout::get());
+			PendingVerifications::<T>::insert(
+				query_id,
+				PendingVerification {
+					requester: sender.clone(),
+					para,
+					claim: claim.clone(),
+					deposit,
+					expires_at,
+				},
+			);
+
+			Self::deposit_event(Event::VerificationRequested(sender, para, query_id, claim));
+			Ok(().into())
+		}
+
+		// 兄弟平行链通过XCM Transact调用，查询本链的存证
+		// 查询结果(所有者, 存证时的区块高度)通过claim_verified发回给对方，存证不存在时为None
+		#[pallet::weight(T::WeightInfo::verify_claim())]
+		pub fn verify_claim(
+			origin: OriginFor<T>,
+			query_id: u64,
+			claim: Vec<u8>,
+		) -> DispatchResultWithPostInfo {
+			// 只接受来自兄弟平行链的查询
+			let para = ensure_sibling_para(<T as Config>::Origin::from(origin))?;
+
+			// 跨链消息不经过交易费，发送结果之前从对方的主权账户收取费用，收取的费用被销毁
+			T::Currency::withdraw(
+				&T::SiblingAccount::convert(para),
+				T::VerificationDeposit::get(),
+				WithdrawReasons::FEE,
+				KeepAlive,
+			)
+			.map_err(|_| Error::<T>::SiblingCannotPay)?;
+
+			let result = Proofs::<T>::get(&claim);
+			let message = Xcm(vec![Transact {
+				origin_type: OriginKind::Native,
+				require_weight_at_most: T::XcmWeightAtMost::get(),
+				call: <T as Config>::Call::from(Call::<T>::claim_verified {
+					query_id,
+					claim: claim.clone(),
+					result: result.clone(),
+				})
+				.encode()
+				.into(),
+			}]);
+			match T::XcmSender::send_xcm((1, Junction::Parachain(para.into())), message) {
+				Ok(()) => Self::deposit_event(Event::ClaimVerified(para, query_id, claim, result)),
+				Err(e) => Self::deposit_event(Event::ErrorSendingVerification(e, para, query_id)),
+			}
+			Ok(().into())
+		}
+
+		// 兄弟平行链通过XCM Transact调用，返回本链之前发起的查询的结果
+		#[pallet::weight(T::WeightInfo::claim_verified())]
+		pub fn claim_verified(
+			origin: OriginFor<T>,
+			query_id: u64,
+			claim: Vec<u8>,
+			result: Option<(T::AccountId, T::BlockNumber)>,
+		) -> DispatchResultWithPostInfo {
+			// 只接受来自兄弟平行链的结果
+			let para = ensure_sibling_para(<T as Config>::Origin::from(origin))?;
+
+			// 查询编号、目标平行链和存证都要与发出的查询一致
+			match PendingVerifications::<T>::get(query_id) {
+				Some(pending) if pending.para == para && pending.claim == claim => {
+					PendingVerifications::<T>::remove(query_id);
+					T::Currency::unreserve(&pending.requester, pending.deposit);
+					Self::deposit_event(Event::VerificationReceived(para, query_id, claim, result));
+				},
+				_ => Self::deposit_event(Event::UnknownVerification(para, query_id)),
+			}
+			Ok(().into())
+		}
+
+		// 清除已经超时、仍然没有收到结果的跨链查询，并将质押退回给发起查询的账户
+		// 任何账户都可以调用
+		#[pallet::weight(T::WeightInfo::cancel_verification())]
+		pub fn cancel_verification(origin: OriginFor<T>, query_id: u64) -> DispatchResultWithPostInfo {
+			ensure_signed(origin)?;
+
+			let pending =
+				PendingVerifications::<T>::get(query_id).ok_or(Error::<T>::VerificationNotFound)?;
+			ensure!(
+				frame_system::Pallet::<T>::block_number() > pending.expires_at,
+				Error::<T>::VerificationNotExpired
+			);
+
+			PendingVerifications::<T>::remove(query_id);
+			T::Currency::unreserve(&pending.requester, pending.deposit);
+
+			Self::deposit_event(Event::VerificationTimedOut(pending.requester, pending.para, query_id));
+			Ok(().into())
+		}
+	}
+
+	#[pallet::validate_unsigned]
+	impl<T: Config> ValidateUnsigned for Pallet<T> {
+		type Call = Call<T>;
+
+		// 交易进入交易池之前校验链下声明，无效的声明不会被打包
+		fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+			let (attestation, signature) = match call {
+				Call::create_claim_with_signature { attestation, signature } =>
+					(attestation, signature),
+				_ => return InvalidTransaction::Call.into(),
+			};
+
+			let current_block = frame_system::Pallet::<T>::block_number();
+			if attestation.deadline < current_block {
+				return InvalidTransaction::Stale.into()
+			}
+			let expected_nonce = AttestationNonces::<T>::get(&attestation.owner);
+			if attestation.nonce < expected_nonce {
+				return InvalidTransaction::Stale.into()
+			}
+			if attestation.nonce > expected_nonce {
+				return InvalidTransaction::Future.into()
+			}
+			if attestation.claim.len() > T::MaxAddend::get() {
+				return InvalidTransaction::Custom(INVALID_CLAIM_LENGTH).into()
+			}
+			if Proofs::<T>::contains_key(&attestation.claim) {
+				return InvalidTransaction::Custom(CLAIM_ALREADY_EXIST).into()
+			}
+			// 所有者必须能够支付质押，并且存证索引还有空位，否则交易执行时一定失败，
+			// 而失败的交易会回滚序号，同一个声明可以被无限次提交，白白占用区块空间
+			let deposit = Self::claim_deposit(&attestation.claim, None);
+			if !T::Currency::can_reserve(&attestation.owner, deposit) {
+				return InvalidTransaction::Payment.into()
+			}
+			if ClaimsOf::<T>::decode_len(&attestation.owner).unwrap_or(0) >=
+				T::MaxClaimsPerOwner::get() as usize
+			{
+				return InvalidTransaction::Custom(TOO_MANY_CLAIMS).into()
+			}
+			if !signature.verify(&Self::attestation_payload(attestation)[..], &attestation.owner) {
+				return InvalidTransaction::BadProof.into()
+			}
+
+			// 同一个所有者的同一个序号只能有一笔交易，声明在截止区块之后从交易池中移除
+			ValidTransaction::with_tag_prefix("ProofAttestation")
+				.priority(T::UnsignedPriority::get())
+				.and_provides((attestation.owner.clone(), attestation.nonce))
+				.longevity(
+					attestation
+						.deadline
+						.saturating_sub(current_block)
+						.saturated_into::<u64>()
+						.max(1),
+				)
+				.propagate(true)
+				.build()
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		// 校验链下声明的截止区块、序号以及存证长度，不包括签名
+		fn check_attestation(
+			attestation: &ClaimAttestation<T::AccountId, T::BlockNumber>,
+		) -> DispatchResult {
+			ensure!(
+				attestation.deadline >= frame_system::Pallet::<T>::block_number(),
+				Error::<T>::AttestationExpired
+			);
+			ensure!(
+				attestation.nonce == AttestationNonces::<T>::get(&attestation.owner),
+				Error::<T>::InvalidAttestationNonce
+			);
+			ensure!(attestation.claim.len() <= T::MaxAddend::get(), Error::<T>::ClaimOutLength);
+			Ok(())
+		}
+
+		// 链下声明需要签名的内容：前缀 + 创世区块hash + 声明的SCALE编码
+		pub fn attestation_payload(
+			attestation: &ClaimAttestation<T::AccountId, T::BlockNumber>,
+		) -> Vec<u8> {
+			let genesis_hash = frame_system::Pallet::<T>::block_hash(T::BlockNumber::zero());
+			(ATTESTATION_CONTEXT, genesis_hash, attestation).encode()
+		}
+
+		// 校验文件的hash值leaf是否包含在已经锚定的Merkle根root中，供runtime api使用
+		// index为leaf在Merkle树中的位置，proof为从叶子到根的路径上的兄弟节点
+		pub fn verify_merkle_inclusion(
+			root: [u8; 32],
+			leaf: [u8; 32],
+			index: u32,
+			proof: Vec<[u8; 32]>,
+		) -> bool {
+			match MerkleLeafCount::<T>::get(root.to_vec()) {
+				Some(leaf_count) => merkle::verify_proof(&root, &leaf, index, leaf_count, &proof),
+				None => false,
+			}
+		}
+
+		// 查询存证是否存在，供runtime api使用
+		pub fn claim_exists(claim: &[u8]) -> bool {
+			Proofs::<T>::contains_key(claim)
+		}
+
+		// 查询指定账户拥有的所有存证，供runtime api使用
+		pub fn claims_by_owner(account: &T::AccountId) -> Vec<Vec<u8>> {
+			ClaimsOf::<T>::get(account).into_inner()
+		}
+
+		// 判断who是否是存证的所有者，或者是所有者授权的操作员
+		pub fn is_owner_or_operator(who: &T::AccountId, owner: &T::AccountId, claim: &[u8]) -> bool {
+			who == owner ||
+				OwnerOperators::<T>::contains_key(owner, who) ||
+				ClaimOperators::<T>::get(claim).contains(who)
+		}
+
+		// 将存证从owner转移给rec_account，更新账户的存证索引并移除交易市场中的挂单
+		// owner的质押随存证一起转移给rec_account，rec_account的可用余额不会被扣除
+		// 调用方需要先校验owner的身份，并保证在事务中调用
+		fn do_transfer_claim(
+			claim: &[u8],
+			owner: &T::AccountId,
+			block_number: T::BlockNumber,
+			rec_account: T::AccountId,
+		) -> DispatchResult {
+			// 转移给自己时索引不需要变化；否则先加入接收方的索引（可能超过上限），再从原所有者的索引中移除
+			if &rec_account != owner {
+				Self::add_claim_to_owner(&rec_account, claim)?;
+				Self::remove_claim_from_owner(owner, claim);
+
+				let deposit = ClaimDeposits::<T>::get(claim);
+				let unmoved = T::Currency::repatriate_reserved(
+					owner,
+					&rec_account,
+					deposit,
+					BalanceStatus::Reserved,
+				)?;
+				ensure!(unmoved.is_zero(), Error::<T>::NoSufficientBalance);
+
+				Self::record_history(
+					claim,
+					ProvenanceAction::Transferred(owner.clone(), rec_account.clone()),
+				);
+			}
+
+			Proofs::<T>::insert(claim, (rec_account, block_number));
+			// 挂单和操作员都是原所有者设置的，所有权变化后失效
+			ClaimListings::<T>::remove(claim);
+			ClaimOperators::<T>::remove(claim);
+
+			Ok(())
+		}
+
+		// 创建存证：质押、更新账户的存证索引和过期队列，并写入Proofs
+		// 调用方需要先校验存证的长度，并保证在事务中调用
+		fn do_create_claim(
+			sender: &T::AccountId,
+			claim: &[u8],
+			ttl: Option<T::BlockNumber>,
+		) -> DispatchResult {
+			// 判断当前的存储单元中，是否已经存在了这样的存证记录，如果存在了，那就报已经存在的错误
+			ensure!(!Proofs::<T>::contains_key(claim), Error::<T>::ProofAlreadyExist);
+
+			// 创建存证之前需要质押一定的金额，防止随意占用链上存储
+			let deposit = Self::claim_deposit(claim, None);
+			T::Currency::reserve(sender, deposit).map_err(|_| Error::<T>::NoSufficientBalance)?;
+			ClaimDeposits::<T>::insert(claim, deposit);
+
+			// 更新账户的存证索引，超过上限就报错
+			Self::add_claim_to_owner(sender, claim)?;
+
+			let current_block = frame_system::Pallet::<T>::block_number();
+
+			// 有有效期的存证加入过期队列，在过期的区块中被移除
+			if let Some(ttl) = ttl {
+				ensure!(!ttl.is_zero(), Error::<T>::InvalidTtl);
+				let expires_at = current_block.saturating_add(ttl);
+				ExpiringClaims::<T>::try_mutate(expires_at, |claims| {
+					claims.try_push(claim.to_vec()).map_err(|_| Error::<T>::TooManyExpiringClaims)
+				})?;
+				ClaimExpiry::<T>::insert(claim, expires_at);
+			}
+
+			// 向链上存储单元中插入一条存证记录
+			Proofs::<T>::insert(
+				claim,                           /* key为 存证的hash值 */
+				(sender.clone(), current_block), /* value为 一个元组，其中包含(AccountId,BlockNumber) */
+			);
+			Self::record_history(claim, ProvenanceAction::Created(sender.clone()));
+
+			Ok(())
+		}
+
+		// 在存证的溯源记录末尾追加一条记录
+		// 记录数量达到上限时保留第一条创建记录，丢弃之后最早的记录，保证总能查到存证的创建者
+		// 最后一个位置留给注销和过期记录，保证存证被移除时的最后一条记录不会因为记录已满而丢失
+		fn record_history(
+			claim: &[u8],
+			action: ProvenanceAction<T::AccountId, T::BlockNumber>,
+		) {
+			let limit = match action {
+				ProvenanceAction::Revoked { .. } | ProvenanceAction::Expired { .. } =>
+					T::MaxHistoryLen::get(),
+				_ => T::MaxHistoryLen::get().saturating_sub(1),
+			} as usize;
+			let record =
+				ProvenanceRecord { action, block_number: frame_system::Pallet::<T>::block_number() };
+			ClaimHistory::<T>::mutate(claim, |history| {
+				while history.len() >= limit && history.len() > 1 {
+					history.remove(1);
+				}
+				if history.len() < limit {
+					let _ = history.try_push(record);
+				}
+			});
+		}
+
+		// 移除存证以及与它相关的所有存储，并退回所有者的质押
+		// 注销存证和存证过期时调用
+		fn do_remove_claim(claim: &[u8], owner: &T::AccountId) {
+			Proofs::<T>::remove(claim);
+			Self::remove_claim_from_owner(owner, claim);
+			// 移除的存证不能继续在交易市场出售
+			ClaimListings::<T>::remove(claim);
+			Metadata::<T>::remove(claim);
+			MerkleLeafCount::<T>::remove(claim);
+			ClaimOperators::<T>::remove(claim);
+			// 溯源记录保留，调用方需要在移除之前追加注销或过期记录
+			// 退回创建存证时的质押
+			T::Currency::unreserve(owner, ClaimDeposits::<T>::take(claim));
+			// 从过期队列中移除（存证过期时该区块的队列已经被取出，这里不会有影响）
+			if let Some(expires_at) = ClaimExpiry::<T>::take(claim) {
+				ExpiringClaims::<T>::mutate_exists(expires_at, |maybe_claims| {
+					if let Some(claims) = maybe_claims {
+						claims.retain(|c| c.as_slice() != claim);
+						if claims.is_empty() {
+							*maybe_claims = None;
+						}
+					}
+				});
+			}
+		}
+
+		// 计算存证需要质押的金额：基础金额 + 每字节金额 * (存证长度 + 元数据编码后的长度)
+		pub fn claim_deposit(claim: &[u8], metadata: Option<&ClaimMetadata>) -> BalanceOf<T> {
+			let bytes = claim.len() + metadata.map_or(0, |m| m.encoded_size());
+			let bytes: BalanceOf<T> = (bytes as u32).into();
+			T::ClaimDepositBase::get()
+				.saturating_add(T::ClaimDepositPerByte::get().saturating_mul(bytes))
+		}
+
+		// 将存证加入账户的存证索引
+		pub(crate) fn add_claim_to_owner(owner: &T::AccountId, claim: &[u8]) -> DispatchResult {
+			ClaimsOf::<T>::try_mutate(owner, |claims| {
+				claims.try_push(claim.to_vec()).map_err(|_| Error::<T>::TooManyClaims.into())
+			})
+		}
+
+		// 从账户的存证索引中移除存证，索引为空时直接删除该存储项
+		pub(crate) fn remove_claim_from_owner(owner: &T::AccountId, claim: &[u8]) {
+			ClaimsOf::<T>::mutate_exists(owner, |maybe_claims| {
+				if let Some(claims) = maybe_claims {
+					claims.retain(|c| c.as_slice() != claim);
+					if claims.is_empty() {
+						*maybe_claims = None;
+					}
+				}
+			});
+		}
 	}
 }
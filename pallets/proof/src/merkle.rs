@@ -0,0 +1,109 @@
+//! 批量存证使用的Merkle树
+//!
+//! 叶子节点就是文件的hash值，父节点为`blake2_256(左子节点 ++ 右子节点)`。
+//! 某一层的节点数量为奇数时，最后一个节点直接提升到上一层。
+//! 校验函数不依赖链上存储，runtime和链下的客户端都可以使用同一份实现。
+
+use frame_support::sp_io::hashing::blake2_256;
+#[cfg(feature = "std")]
+use sp_std::vec::Vec;
+
+/// Merkle树中节点的hash值
+pub type Hash = [u8; 32];
+
+fn hash_pair(left: &Hash, right: &Hash) -> Hash {
+	let mut data = [0u8; 64];
+	data[..32].copy_from_slice(left);
+	data[32..].copy_from_slice(right);
+	blake2_256(&data)
+}
+
+/// 校验叶子节点leaf是否位于叶子数量为leaf_count、根为root的Merkle树的第index个位置
+///
+/// proof为从叶子到根的路径上依次经过的兄弟节点，没有兄弟节点的层不需要提供
+pub fn verify_proof(
+	root: &Hash,
+	leaf: &Hash,
+	index: u32,
+	leaf_count: u32,
+	proof: &[Hash],
+) -> bool {
+	if index >= leaf_count {
+		return false
+	}
+
+	let mut node = *leaf;
+	let mut index = index;
+	let mut width = leaf_count;
+	let mut siblings = proof.iter();
+
+	while width > 1 {
+		if index % 2 == 1 {
+			// 当前节点是右子节点，兄弟节点在左边
+			match siblings.next() {
+				Some(sibling) => node = hash_pair(sibling, &node),
+				None => return false,
+			}
+		} else if index + 1 < width {
+			// 当前节点是左子节点，兄弟节点在右边
+			match siblings.next() {
+				Some(sibling) => node = hash_pair(&node, sibling),
+				None => return false,
+			}
+		}
+		// 否则当前节点是奇数层的最后一个节点，直接提升到上一层
+		index /= 2;
+		// 向上取整，不能写成(width + 1) / 2，width为u32::MAX时会溢出
+		width = width / 2 + width % 2;
+	}
+
+	// 多余的兄弟节点同样视为无效的证明
+	siblings.next().is_none() && &node == root
+}
+
+#[cfg(feature = "std")]
+fn next_level(level: &[Hash]) -> Vec<Hash> {
+	level
+		.chunks(2)
+		.map(|pair| match pair {
+			[left, right] => hash_pair(left, right),
+			[single] => *single,
+			_ => unreachable!("chunks(2) yields one or two elements; qed"),
+		})
+		.collect()
+}
+
+/// 根据所有叶子节点计算Merkle根，没有叶子节点时返回None
+#[cfg(feature = "std")]
+pub fn merkle_root(leaves: &[Hash]) -> Option<Hash> {
+	if leaves.is_empty() {
+		return None
+	}
+
+	let mut level = leaves.to_vec();
+	while level.len() > 1 {
+		level = next_level(&level);
+	}
+	Some(level[0])
+}
+
+/// 生成第index个叶子节点的Merkle证明，可以直接传给[`verify_proof`]
+#[cfg(feature = "std")]
+pub fn merkle_proof(leaves: &[Hash], index: usize) -> Option<Vec<Hash>> {
+	if index >= leaves.len() {
+		return None
+	}
+
+	let mut proof = Vec::new();
+	let mut level = leaves.to_vec();
+	let mut index = index;
+	while level.len() > 1 {
+		let sibling = index ^ 1;
+		if sibling < level.len() {
+			proof.push(level[sibling]);
+		}
+		level = next_level(&level);
+		index /= 2;
+	}
+	Some(proof)
+}
@@ -0,0 +1,42 @@
+// 存证模块的存储迁移
+
+pub mod v1 {
+	use crate::{ClaimsOf, Config, Pallet, Proofs};
+	use frame_support::{
+		traits::{Get, GetStorageVersion, StorageVersion},
+		weights::Weight,
+	};
+
+	/// 版本0 -> 版本1：根据已有的Proofs构建账户的存证索引ClaimsOf
+	///
+	/// 超过`MaxClaimsPerOwner`的存证不会写入索引，只会打印警告日志
+	pub fn migrate<T: Config>() -> Weight {
+		let on_chain_version = Pallet::<T>::on_chain_storage_version();
+		if on_chain_version >= 1 {
+			log::info!(
+				target: "runtime::proof",
+				"skipping v1 migration: on-chain storage version is {:?}",
+				on_chain_version,
+			);
+			return T::DbWeight::get().reads(1)
+		}
+
+		let mut count = 0u64;
+		for (claim, (owner, _)) in Proofs::<T>::iter() {
+			count += 1;
+			if ClaimsOf::<T>::try_mutate(&owner, |claims| claims.try_push(claim)).is_err() {
+				log::warn!(
+					target: "runtime::proof",
+					"claim index of {:?} is full, claim left out of ClaimsOf",
+					owner,
+				);
+			}
+		}
+
+		StorageVersion::new(1).put::<Pallet<T>>();
+		log::info!(target: "runtime::proof", "v1 migration indexed {} claims", count);
+
+		// 每个存证读取一次Proofs和ClaimsOf、写入一次ClaimsOf，最后读写一次存储版本
+		T::DbWeight::get().reads_writes(count * 2 + 1, count + 1)
+	}
+}
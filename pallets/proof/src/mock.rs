@@ -0,0 +1,201 @@
+use crate as pallet_proof;
+use cumulus_primitives_core::ParaId;
+use frame_support::{parameter_types, traits::Get};
+use frame_system as system;
+use sp_core::H256;
+use sp_runtime::{
+	testing::{Header, TestSignature, UintAuthorityId},
+	traits::{BlakeTwo256, Convert, IdentityLookup},
+};
+use std::cell::RefCell;
+use xcm::latest::prelude::*;
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+pub type Balance = u128;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+		CumulusXcm: cumulus_pallet_xcm::{Pallet, Event<T>, Origin},
+		ProofModule: pallet_proof::{Pallet, Call, Storage, Event<T>, Config<T>, ValidateUnsigned},
+	}
+);
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const SS58Prefix: u8 = 42;
+}
+
+impl system::Config for Test {
+	type BaseCallFilter = frame_support::traits::Everything;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type Origin = Origin;
+	type Call = Call;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<Balance>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = SS58Prefix;
+	type OnSetCode = ();
+	type MaxConsumers = frame_support::traits::ConstU32<16>;
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: Balance = 1;
+	pub const MaxLocks: u32 = 50;
+}
+
+impl pallet_balances::Config for Test {
+	type MaxLocks = MaxLocks;
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+	type Balance = Balance;
+	type Event = Event;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
+impl cumulus_pallet_xcm::Config for Test {
+	type Event = Event;
+	type XcmExecutor = ();
+}
+
+thread_local! {
+	// 测试中发送的跨链消息：(目标位置, 消息)
+	pub static SENT_XCM: RefCell<Vec<(MultiLocation, Xcm<()>)>> = RefCell::new(Vec::new());
+	// 存证的最大长度，默认为32个字节，刚好可以锚定Merkle根，可以在测试中修改
+	pub static MAX_ADDEND: RefCell<usize> = RefCell::new(32);
+}
+
+pub struct MaxAddend;
+impl Get<usize> for MaxAddend {
+	fn get() -> usize {
+		MAX_ADDEND.with(|v| *v.borrow())
+	}
+}
+
+/// 设置存证的最大长度
+pub fn set_max_addend(value: usize) {
+	MAX_ADDEND.with(|v| *v.borrow_mut() = value);
+}
+
+/// 取出测试中发送的所有跨链消息
+pub fn sent_xcm() -> Vec<(MultiLocation, Xcm<()>)> {
+	SENT_XCM.with(|q| q.borrow_mut().drain(..).collect())
+}
+
+/// 测试用的跨链消息发送模块，只记录消息不真正发送；发往平行链9999的消息发送失败
+pub struct TestSendXcm;
+impl SendXcm for TestSendXcm {
+	fn send_xcm(dest: impl Into<MultiLocation>, msg: Xcm<()>) -> SendResult {
+		let dest = dest.into();
+		if dest == (1, Junction::Parachain(9999)).into() {
+			return Err(SendError::CannotReachDestination(dest, msg))
+		}
+		SENT_XCM.with(|q| q.borrow_mut().push((dest, msg)));
+		Ok(())
+	}
+}
+
+parameter_types! {
+	pub const MaxClaimsPerOwner: u32 = 3;
+	// 创建存证的质押为 2 + 1 * 存证长度
+	pub const ClaimDepositBase: Balance = 2;
+	pub const ClaimDepositPerByte: Balance = 1;
+	pub const MaxExpiringPerBlock: u32 = 2;
+	pub const MaxMetadataLen: usize = 16;
+	pub const MaxClaimOperators: u32 = 2;
+	pub const MaxHistoryLen: u32 = 3;
+	pub const XcmWeightAtMost: u64 = 1_000;
+	// 跨链查询的质押为5，10个区块后超时
+	pub const VerificationDeposit: Balance = 5;
+	pub const VerificationTimeout: u64 = 10;
+	pub const UnsignedPriority: u64 = 100;
+}
+
+/// 兄弟平行链的主权账户为1000 + 平行链编号
+pub struct SiblingAccount;
+impl Convert<ParaId, u64> for SiblingAccount {
+	fn convert(para: ParaId) -> u64 {
+		1000 + u64::from(u32::from(para))
+	}
+}
+
+impl pallet_proof::Config for Test {
+	type Event = Event;
+	type Currency = Balances;
+	type MaxAddend = MaxAddend;
+	type MaxClaimsPerOwner = MaxClaimsPerOwner;
+	type ClaimDepositBase = ClaimDepositBase;
+	type ClaimDepositPerByte = ClaimDepositPerByte;
+	type MaxExpiringPerBlock = MaxExpiringPerBlock;
+	type MaxMetadataLen = MaxMetadataLen;
+	type MaxClaimOperators = MaxClaimOperators;
+	type MaxHistoryLen = MaxHistoryLen;
+	type Origin = Origin;
+	type Call = Call;
+	type XcmSender = TestSendXcm;
+	type XcmWeightAtMost = XcmWeightAtMost;
+	type VerificationDeposit = VerificationDeposit;
+	type VerificationTimeout = VerificationTimeout;
+	type SiblingAccount = SiblingAccount;
+	type WeightInfo = ();
+	type Signature = TestSignature;
+	type Signer = UintAuthorityId;
+	type UnsignedPriority = UnsignedPriority;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = MockBenchmarkHelper;
+}
+
+/// benchmark中使用账户1签名，TestSignature记录签名者和被签名的内容
+#[cfg(feature = "runtime-benchmarks")]
+pub struct MockBenchmarkHelper;
+#[cfg(feature = "runtime-benchmarks")]
+impl pallet_proof::benchmarking::BenchmarkHelper<u64, TestSignature> for MockBenchmarkHelper {
+	fn signer() -> u64 {
+		1
+	}
+
+	fn sign(signer: &u64, payload: &[u8]) -> TestSignature {
+		TestSignature(*signer, payload.to_vec())
+	}
+}
+
+// Build genesis storage according to the mock runtime.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		// 账户1、2、3的余额分别为100、200、300，平行链2000和9999的主权账户余额为100
+		balances: vec![(1, 100), (2, 200), (3, 300), (3000, 100), (10999, 100)],
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+
+	let mut ext = sp_io::TestExternalities::new(t);
+	// 设置起始区块高度，区块0不会记录事件
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
@@ -0,0 +1,1219 @@
+use super::*;
+use crate::mock::{Call, Event, *};
+use codec::Encode;
+use sp_core::H256;
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::{GenesisBuild, Get, Hooks, ReservableCurrency},
+};
+use frame_support::unsigned::ValidateUnsigned;
+use sp_runtime::{
+	testing::TestSignature,
+	transaction_validity::{InvalidTransaction, TransactionSource, TransactionValidity},
+};
+use xcm::latest::{Instruction::Transact, Junction, OriginKind, Xcm};
+
+/// 创建存证的测试
+#[test]
+fn create_claim_works() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		assert_ok!(ProofModule::create_claim(Origin::signed(1), claim.clone(), None));
+
+		// 存证记录了所有者和创建时的区块高度，并加入所有者的存证索引
+		assert_eq!(Proofs::<Test>::get(&claim), Some((1, System::block_number())));
+		assert_eq!(ProofModule::claims_by_owner(&1), vec![claim.clone()]);
+		assert!(ProofModule::claim_exists(&claim));
+		System::assert_last_event(Event::ProofModule(crate::Event::ClaimCreated(1, claim)));
+	})
+}
+
+/// 存证已经存在时不能重复创建
+#[test]
+fn create_claim_failed_when_claim_already_exist() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		assert_ok!(ProofModule::create_claim(Origin::signed(1), claim.clone(), None));
+
+		// 其他账户也不能创建相同的存证
+		assert_noop!(
+			ProofModule::create_claim(Origin::signed(1), claim.clone(), None),
+			Error::<Test>::ProofAlreadyExist
+		);
+		assert_noop!(
+			ProofModule::create_claim(Origin::signed(2), claim, None),
+			Error::<Test>::ProofAlreadyExist
+		);
+	})
+}
+
+/// 存证的长度不能超过MaxAddend
+#[test]
+fn create_claim_failed_when_claim_out_length() {
+	new_test_ext().execute_with(|| {
+		// 刚好等于上限的存证可以创建
+		let claim = vec![0; MaxAddend::get()];
+		assert_ok!(ProofModule::create_claim(Origin::signed(1), claim, None));
+
+		let claim = vec![1; MaxAddend::get() + 1];
+		assert_noop!(
+			ProofModule::create_claim(Origin::signed(1), claim, None),
+			Error::<Test>::ClaimOutLength
+		);
+	})
+}
+
+/// 账户拥有的存证数量不能超过MaxClaimsPerOwner
+#[test]
+fn create_claim_failed_when_too_many_claims() {
+	new_test_ext().execute_with(|| {
+		for i in 0..MaxClaimsPerOwner::get() as u8 {
+			assert_ok!(ProofModule::create_claim(Origin::signed(1), vec![i], None));
+		}
+		assert_noop!(
+			ProofModule::create_claim(Origin::signed(1), vec![9], None),
+			Error::<Test>::TooManyClaims
+		);
+
+		// 接收方的存证数量达到上限时也不能接收转移
+		assert_ok!(ProofModule::create_claim(Origin::signed(2), vec![9], None));
+		assert_noop!(
+			ProofModule::transfer_claim(Origin::signed(2), vec![9], 1),
+			Error::<Test>::TooManyClaims
+		);
+	})
+}
+
+/// 注销存证的测试
+#[test]
+fn revoke_claim_works() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		assert_ok!(ProofModule::create_claim(Origin::signed(1), claim.clone(), None));
+
+		assert_ok!(ProofModule::revoke_claim(Origin::signed(1), claim.clone()));
+		assert_eq!(Proofs::<Test>::get(&claim), None);
+		assert!(ProofModule::claims_by_owner(&1).is_empty());
+		System::assert_last_event(Event::ProofModule(crate::Event::ClaimRevoked(1, claim.clone())));
+
+		// 注销后可以重新创建
+		assert_ok!(ProofModule::create_claim(Origin::signed(2), claim, None));
+	})
+}
+
+/// 注销不存在的存证
+#[test]
+fn revoke_claim_failed_when_claim_is_not_exist() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			ProofModule::revoke_claim(Origin::signed(1), vec![0, 1]),
+			Error::<Test>::ClaimNotExist
+		);
+	})
+}
+
+/// 只有存证的所有者可以注销存证
+#[test]
+fn revoke_claim_failed_with_wrong_owner() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		assert_ok!(ProofModule::create_claim(Origin::signed(1), claim.clone(), None));
+
+		assert_noop!(
+			ProofModule::revoke_claim(Origin::signed(2), claim),
+			Error::<Test>::NotClaimOwner
+		);
+	})
+}
+
+/// 转移存证的测试
+#[test]
+fn transfer_claim_works() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		assert_ok!(ProofModule::create_claim(Origin::signed(1), claim.clone(), None));
+
+		System::set_block_number(5);
+		assert_ok!(ProofModule::transfer_claim(Origin::signed(1), claim.clone(), 2));
+
+		// 转移后保留存证创建时的区块高度，所有者的存证索引随之更新
+		assert_eq!(Proofs::<Test>::get(&claim), Some((2, 1)));
+		assert!(ProofModule::claims_by_owner(&1).is_empty());
+		assert_eq!(ProofModule::claims_by_owner(&2), vec![claim.clone()]);
+		System::assert_last_event(Event::ProofModule(crate::Event::ClaimTransfered(
+			1,
+			claim.clone(),
+		)));
+
+		// 原所有者不能再操作该存证
+		assert_noop!(
+			ProofModule::transfer_claim(Origin::signed(1), claim, 3),
+			Error::<Test>::NotClaimOwner
+		);
+	})
+}
+
+/// 转移不存在的存证
+#[test]
+fn transfer_claim_failed_when_claim_is_not_exist() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			ProofModule::transfer_claim(Origin::signed(1), vec![0, 1], 2),
+			Error::<Test>::ClaimNotExist
+		);
+	})
+}
+
+/// 只有存证的所有者可以转移存证
+#[test]
+fn transfer_claim_failed_with_wrong_owner() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0, 1];
+		assert_ok!(ProofModule::create_claim(Origin::signed(1), claim.clone(), None));
+
+		assert_noop!(
+			ProofModule::transfer_claim(Origin::signed(2), claim, 3),
+			Error::<Test>::NotClaimOwner
+		);
+	})
+}
+
+/// 创世配置中的存证写入存储，区块高度记为0
+#[test]
+fn genesis_config_works() {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	crate::GenesisConfig::<Test> { claims: vec![(1, vec![0, 1]), (2, vec![2])] }
+		.assimilate_storage(&mut t)
+		.unwrap();
+
+	sp_io::TestExternalities::new(t).execute_with(|| {
+		assert_eq!(Proofs::<Test>::get(vec![0, 1]), Some((1, 0)));
+		assert_eq!(Proofs::<Test>::get(vec![2]), Some((2, 0)));
+		assert_eq!(ProofModule::claims_by_owner(&1), vec![vec![0, 1]]);
+		assert_eq!(ProofModule::claims_by_owner(&2), vec![vec![2]]);
+	})
+}
+
+/// 版本1的迁移根据已有的存证构建账户的存证索引，并且只执行一次
+#[test]
+fn migration_v1_builds_claim_index() {
+	new_test_ext().execute_with(|| {
+		use frame_support::traits::{GetStorageVersion, StorageVersion};
+
+		// 模拟升级前的存储：只有Proofs，没有存证索引
+		StorageVersion::new(0).put::<ProofModule>();
+		Proofs::<Test>::insert(vec![0], (1, 1));
+		Proofs::<Test>::insert(vec![1], (1, 1));
+		Proofs::<Test>::insert(vec![2], (2, 1));
+
+		crate::migrations::v1::migrate::<Test>();
+
+		assert_eq!(ProofModule::on_chain_storage_version(), 1);
+		let mut claims = ProofModule::claims_by_owner(&1);
+		claims.sort();
+		assert_eq!(claims, vec![vec![0], vec![1]]);
+		assert_eq!(ProofModule::claims_by_owner(&2), vec![vec![2]]);
+
+		// 再次执行迁移不会重复写入索引
+		crate::migrations::v1::migrate::<Test>();
+		assert_eq!(ProofModule::claims_by_owner(&2), vec![vec![2]]);
+	})
+}
+
+/// 挂单出售存证的测试
+#[test]
+fn sell_claim_works() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![1, 2];
+		assert_ok!(ProofModule::create_claim(Origin::signed(1), claim.clone(), None));
+
+		// 账户1将存证以20的价格挂单
+		assert_ok!(ProofModule::sell_claim(Origin::signed(1), claim.clone(), 20));
+		assert_eq!(ClaimListings::<Test>::get(&claim), Some(20));
+		System::assert_last_event(Event::ProofModule(crate::Event::ClaimListed(
+			1,
+			claim.clone(),
+			20,
+		)));
+
+		// 再次挂单会更新价格
+		assert_ok!(ProofModule::sell_claim(Origin::signed(1), claim.clone(), 30));
+		assert_eq!(ClaimListings::<Test>::get(&claim), Some(30));
+
+		// 不是所有者不能挂单，不存在的存证不能挂单
+		assert_noop!(
+			ProofModule::sell_claim(Origin::signed(2), claim.clone(), 10),
+			Error::<Test>::NotClaimOwner
+		);
+		assert_noop!(
+			ProofModule::sell_claim(Origin::signed(1), vec![9], 10),
+			Error::<Test>::ClaimNotExist
+		);
+	})
+}
+
+/// 取消挂单的测试
+#[test]
+fn cancel_claim_sale_works() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![1, 2];
+		assert_ok!(ProofModule::create_claim(Origin::signed(1), claim.clone(), None));
+
+		// 没有挂单的存证不能取消
+		assert_noop!(
+			ProofModule::cancel_claim_sale(Origin::signed(1), claim.clone()),
+			Error::<Test>::ClaimNotForSale
+		);
+
+		assert_ok!(ProofModule::sell_claim(Origin::signed(1), claim.clone(), 20));
+		assert_noop!(
+			ProofModule::cancel_claim_sale(Origin::signed(2), claim.clone()),
+			Error::<Test>::NotClaimOwner
+		);
+
+		assert_ok!(ProofModule::cancel_claim_sale(Origin::signed(1), claim.clone()));
+		assert_eq!(ClaimListings::<Test>::get(&claim), None);
+		System::assert_last_event(Event::ProofModule(crate::Event::ClaimListingCancelled(
+			1,
+			claim.clone(),
+		)));
+
+		// 取消挂单后就不能再购买
+		assert_noop!(
+			ProofModule::buy_claim(Origin::signed(2), claim, 20),
+			Error::<Test>::ClaimNotForSale
+		);
+	})
+}
+
+/// 购买存证的测试
+#[test]
+fn buy_claim_works() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![1, 2];
+		assert_ok!(ProofModule::create_claim(Origin::signed(1), claim.clone(), None));
+		assert_ok!(ProofModule::sell_claim(Origin::signed(1), claim.clone(), 20));
+
+		// 账户2以不超过25的价格购买
+		assert_ok!(ProofModule::buy_claim(Origin::signed(2), claim.clone(), 25));
+
+		// 所有权和账户的存证索引都转移给账户2，挂单被移除
+		assert_eq!(Proofs::<Test>::get(&claim), Some((2, 1)));
+		assert!(ClaimsOf::<Test>::get(1).is_empty());
+		assert_eq!(ClaimsOf::<Test>::get(2).into_inner(), vec![claim.clone()]);
+		assert_eq!(ClaimListings::<Test>::get(&claim), None);
+
+		// 账户2支付了20给账户1，质押也从账户1转移到了账户2
+		assert_eq!(Balances::free_balance(1), 116);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::free_balance(2), 180);
+		assert_eq!(Balances::reserved_balance(2), 4);
+		System::assert_last_event(Event::ProofModule(crate::Event::ClaimSold(
+			1,
+			2,
+			claim.clone(),
+			20,
+		)));
+
+		// 已经成交的存证不能再次购买
+		assert_noop!(
+			ProofModule::buy_claim(Origin::signed(3), claim, 20),
+			Error::<Test>::ClaimNotForSale
+		);
+	})
+}
+
+/// 购买存证失败的测试
+#[test]
+fn buy_claim_fails() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![1, 2];
+		assert_ok!(ProofModule::create_claim(Origin::signed(1), claim.clone(), None));
+		assert_ok!(ProofModule::sell_claim(Origin::signed(1), claim.clone(), 250));
+
+		// 不能购买自己的存证
+		assert_noop!(
+			ProofModule::buy_claim(Origin::signed(1), claim.clone(), 250),
+			Error::<Test>::BuyOwnClaim
+		);
+		// 挂单价格超过了买家的最高价格
+		assert_noop!(
+			ProofModule::buy_claim(Origin::signed(2), claim.clone(), 100),
+			Error::<Test>::PriceTooHigh
+		);
+		// 余额不足时代币转账失败，存证的所有权不变
+		assert_noop!(
+			ProofModule::buy_claim(Origin::signed(2), claim.clone(), 300),
+			pallet_balances::Error::<Test>::InsufficientBalance
+		);
+		assert_eq!(Proofs::<Test>::get(&claim), Some((1, 1)));
+	})
+}
+
+/// 转移存证会让挂单失效
+#[test]
+fn transfer_claim_cancels_listing() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![1, 2];
+		assert_ok!(ProofModule::create_claim(Origin::signed(1), claim.clone(), None));
+		assert_ok!(ProofModule::sell_claim(Origin::signed(1), claim.clone(), 20));
+
+		assert_ok!(ProofModule::transfer_claim(Origin::signed(1), claim.clone(), 3));
+		assert_eq!(ClaimListings::<Test>::get(&claim), None);
+		assert_noop!(
+			ProofModule::buy_claim(Origin::signed(2), claim, 20),
+			Error::<Test>::ClaimNotForSale
+		);
+	})
+}
+
+/// 创建存证需要质押，注销后退回质押
+#[test]
+fn claim_deposit_reserved_and_released() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![1, 2, 3];
+		assert_ok!(ProofModule::create_claim(Origin::signed(1), claim.clone(), None));
+		// 质押为 2 + 1 * 3 = 5
+		assert_eq!(ClaimDeposits::<Test>::get(&claim), 5);
+		assert_eq!(Balances::reserved_balance(1), 5);
+		assert_eq!(Balances::free_balance(1), 95);
+
+		assert_ok!(ProofModule::revoke_claim(Origin::signed(1), claim.clone()));
+		assert_eq!(ClaimDeposits::<Test>::get(&claim), 0);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::free_balance(1), 100);
+	})
+}
+
+/// 转移存证时质押转移给新的所有者
+#[test]
+fn claim_deposit_moves_on_transfer() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![1, 2, 3];
+		assert_ok!(ProofModule::create_claim(Origin::signed(1), claim.clone(), None));
+		assert_ok!(ProofModule::transfer_claim(Origin::signed(1), claim.clone(), 2));
+
+		// 原所有者的质押转移给新的所有者，新的所有者的可用余额没有被扣除
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::free_balance(1), 95);
+		assert_eq!(Balances::reserved_balance(2), 5);
+		assert_eq!(Balances::free_balance(2), 200);
+
+		// 账户4不存在，质押不能转入
+		assert_noop!(
+			ProofModule::transfer_claim(Origin::signed(2), claim.clone(), 4),
+			pallet_balances::Error::<Test>::DeadAccount
+		);
+
+		// 注销后质押退回给当前的所有者
+		assert_ok!(ProofModule::revoke_claim(Origin::signed(2), claim));
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert_eq!(Balances::free_balance(2), 205);
+	})
+}
+
+/// 把存证转移给没有可用余额的账户时，不会扣除接收方的余额
+#[test]
+fn transfer_claim_does_not_charge_recipient() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![1, 2, 3];
+		assert_ok!(ProofModule::create_claim(Origin::signed(1), claim.clone(), None));
+		// 账户3的余额全部被质押，没有可用余额
+		assert_ok!(Balances::reserve(&3, 299));
+		assert_eq!(Balances::free_balance(3), 1);
+
+		assert_ok!(ProofModule::transfer_claim(Origin::signed(1), claim.clone(), 3));
+		assert_eq!(Balances::free_balance(3), 1);
+		assert_eq!(Balances::reserved_balance(3), 304);
+		assert_eq!(ClaimDeposits::<Test>::get(&claim), 5);
+	})
+}
+
+/// 余额不足时不能创建存证
+#[test]
+fn create_claim_without_deposit_fails() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			ProofModule::create_claim(Origin::signed(4), vec![1], None),
+			Error::<Test>::NoSufficientBalance
+		);
+	})
+}
+
+/// 有有效期的存证在过期的区块被移除
+#[test]
+fn claim_expires() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![1, 2];
+		// 在区块1创建有效期为3个区块的存证，在区块4过期
+		assert_ok!(ProofModule::create_claim(Origin::signed(1), claim.clone(), Some(3)));
+		assert_eq!(ClaimExpiry::<Test>::get(&claim), Some(4));
+		assert_eq!(ExpiringClaims::<Test>::get(4).into_inner(), vec![claim.clone()]);
+		assert_eq!(Balances::reserved_balance(1), 4);
+
+		// 过期之前存证仍然有效
+		System::set_block_number(3);
+		ProofModule::on_initialize(3);
+		assert!(Proofs::<Test>::contains_key(&claim));
+
+		System::set_block_number(4);
+		ProofModule::on_initialize(4);
+		assert!(!Proofs::<Test>::contains_key(&claim));
+		assert!(ClaimsOf::<Test>::get(1).is_empty());
+		assert_eq!(ClaimExpiry::<Test>::get(&claim), None);
+		assert!(!ExpiringClaims::<Test>::contains_key(4));
+		// 过期后退回质押
+		assert_eq!(Balances::reserved_balance(1), 0);
+		System::assert_last_event(Event::ProofModule(crate::Event::ClaimExpired(1, claim)));
+	})
+}
+
+/// 注销有有效期的存证时，同时将它从过期队列中移除
+#[test]
+fn revoke_claim_removes_expiry() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![1, 2];
+		assert_ok!(ProofModule::create_claim(Origin::signed(1), claim.clone(), Some(3)));
+		assert_ok!(ProofModule::revoke_claim(Origin::signed(1), claim.clone()));
+
+		assert_eq!(ClaimExpiry::<Test>::get(&claim), None);
+		assert!(!ExpiringClaims::<Test>::contains_key(4));
+	})
+}
+
+/// 有效期不能为0，同一个区块过期的存证数量有上限
+#[test]
+fn create_claim_with_invalid_ttl_fails() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			ProofModule::create_claim(Origin::signed(1), vec![1], Some(0)),
+			Error::<Test>::InvalidTtl
+		);
+
+		assert_ok!(ProofModule::create_claim(Origin::signed(1), vec![1], Some(5)));
+		assert_ok!(ProofModule::create_claim(Origin::signed(2), vec![2], Some(5)));
+		assert_noop!(
+			ProofModule::create_claim(Origin::signed(3), vec![3], Some(5)),
+			Error::<Test>::TooManyExpiringClaims
+		);
+	})
+}
+
+fn metadata(memo: &[u8]) -> ClaimMetadata {
+	ClaimMetadata {
+		hash_algorithm: HashAlgorithm::Sha2_256,
+		content_type: b"text/plain".to_vec(),
+		uri: None,
+		memo: memo.to_vec(),
+	}
+}
+
+/// 设置和清除存证元数据的测试
+#[test]
+fn set_claim_metadata_works() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![1, 2];
+		assert_ok!(ProofModule::create_claim(Origin::signed(1), claim.clone(), None));
+		assert_eq!(Balances::reserved_balance(1), 4);
+
+		let meta = metadata(b"memo");
+		assert_ok!(ProofModule::set_claim_metadata(
+			Origin::signed(1),
+			claim.clone(),
+			Some(meta.clone())
+		));
+		assert_eq!(Metadata::<Test>::get(&claim), Some(meta.clone()));
+		System::assert_last_event(Event::ProofModule(crate::Event::ClaimMetadataUpdated(
+			1,
+			claim.clone(),
+		)));
+
+		// 元数据按编码后的长度增加质押
+		let deposit = 4 + meta.encoded_size() as u128;
+		assert_eq!(ClaimDeposits::<Test>::get(&claim), deposit);
+		assert_eq!(Balances::reserved_balance(1), deposit);
+
+		// 清除元数据后退回多出的质押
+		assert_ok!(ProofModule::set_claim_metadata(Origin::signed(1), claim.clone(), None));
+		assert_eq!(Metadata::<Test>::get(&claim), None);
+		assert_eq!(Balances::reserved_balance(1), 4);
+	})
+}
+
+/// 只有所有者可以设置元数据，元数据的字段有长度上限
+#[test]
+fn set_claim_metadata_fails() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![1, 2];
+		assert_noop!(
+			ProofModule::set_claim_metadata(Origin::signed(1), claim.clone(), None),
+			Error::<Test>::ClaimNotExist
+		);
+
+		assert_ok!(ProofModule::create_claim(Origin::signed(1), claim.clone(), None));
+		assert_noop!(
+			ProofModule::set_claim_metadata(Origin::signed(2), claim.clone(), Some(metadata(b""))),
+			Error::<Test>::NotClaimOwner
+		);
+		assert_noop!(
+			ProofModule::set_claim_metadata(
+				Origin::signed(1),
+				claim.clone(),
+				Some(metadata(&[0u8; 17]))
+			),
+			Error::<Test>::MetadataOutLength
+		);
+	})
+}
+
+/// 注销存证时同时移除元数据并退回全部质押
+#[test]
+fn revoke_claim_removes_metadata() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![1, 2];
+		assert_ok!(ProofModule::create_claim(Origin::signed(1), claim.clone(), None));
+		assert_ok!(ProofModule::set_claim_metadata(
+			Origin::signed(1),
+			claim.clone(),
+			Some(metadata(b"memo"))
+		));
+
+		assert_ok!(ProofModule::revoke_claim(Origin::signed(1), claim.clone()));
+		assert_eq!(Metadata::<Test>::get(&claim), None);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::free_balance(1), 100);
+	})
+}
+
+fn leaves(count: u8) -> Vec<[u8; 32]> {
+	(0..count).map(|i| [i; 32]).collect()
+}
+
+/// Merkle证明的生成和校验，包括叶子数量为奇数的情况
+#[test]
+fn merkle_proof_roundtrip() {
+	for count in 1..=7u8 {
+		let leaves = leaves(count);
+		let root = merkle::merkle_root(&leaves).unwrap();
+		for (index, leaf) in leaves.iter().enumerate() {
+			let proof = merkle::merkle_proof(&leaves, index).unwrap();
+			assert!(merkle::verify_proof(&root, leaf, index as u32, count as u32, &proof));
+			// 位置错误或者叶子数量错误都无法通过校验
+			assert!(!merkle::verify_proof(&root, leaf, index as u32 + 1, count as u32, &proof));
+			assert!(!merkle::verify_proof(&root, leaf, index as u32, count as u32 + 2, &proof));
+		}
+		// 不在树中的文件无法通过校验
+		let proof = merkle::merkle_proof(&leaves, 0).unwrap();
+		assert!(!merkle::verify_proof(&root, &[0xff; 32], 0, count as u32, &proof));
+	}
+	assert_eq!(merkle::merkle_root(&[]), None);
+}
+
+/// 锚定Merkle根并校验文件是否包含在其中
+#[test]
+fn create_merkle_claim_works() {
+	new_test_ext().execute_with(|| {
+		let leaves = leaves(5);
+		let root = merkle::merkle_root(&leaves).unwrap();
+
+		assert_ok!(ProofModule::create_merkle_claim(Origin::signed(1), root, 5, None));
+		assert_eq!(Proofs::<Test>::get(root.to_vec()), Some((1, 1)));
+		assert_eq!(MerkleLeafCount::<Test>::get(root.to_vec()), Some(5));
+		System::assert_last_event(Event::ProofModule(crate::Event::MerkleClaimCreated(
+			1,
+			root.to_vec(),
+			5,
+		)));
+
+		let proof = merkle::merkle_proof(&leaves, 3).unwrap();
+		assert!(ProofModule::verify_merkle_inclusion(root, leaves[3], 3, proof.clone()));
+		assert!(!ProofModule::verify_merkle_inclusion(root, leaves[2], 3, proof.clone()));
+
+		// 注销后Merkle根不再被锚定
+		assert_ok!(ProofModule::revoke_claim(Origin::signed(1), root.to_vec()));
+		assert_eq!(MerkleLeafCount::<Test>::get(root.to_vec()), None);
+		assert!(!ProofModule::verify_merkle_inclusion(root, leaves[3], 3, proof));
+	})
+}
+
+/// 叶子数量不能为0，Merkle根不能重复锚定
+#[test]
+fn create_merkle_claim_fails() {
+	new_test_ext().execute_with(|| {
+		let root = [7u8; 32];
+		assert_noop!(
+			ProofModule::create_merkle_claim(Origin::signed(1), root, 0, None),
+			Error::<Test>::InvalidLeafCount
+		);
+
+		assert_ok!(ProofModule::create_merkle_claim(Origin::signed(1), root, 1, None));
+		assert_noop!(
+			ProofModule::create_merkle_claim(Origin::signed(2), root, 1, None),
+			Error::<Test>::ProofAlreadyExist
+		);
+
+		// Merkle根的长度超过MaxAddend时不能锚定
+		set_max_addend(16);
+		assert_noop!(
+			ProofModule::create_merkle_claim(Origin::signed(1), [8u8; 32], 1, None),
+			Error::<Test>::ClaimOutLength
+		);
+	})
+}
+
+/// 叶子数量为u32::MAX时计算每一层的宽度不会溢出
+#[test]
+fn merkle_verify_max_leaf_count() {
+	let leaf = [1u8; 32];
+	// 第0个叶子在每一层都有右边的兄弟节点，u32::MAX个叶子的树有32层
+	let proof = vec![[2u8; 32]; 32];
+	let root = proof.iter().fold(leaf, |node, sibling| {
+		let mut data = [0u8; 64];
+		data[..32].copy_from_slice(&node);
+		data[32..].copy_from_slice(sibling);
+		sp_io::hashing::blake2_256(&data)
+	});
+	assert!(merkle::verify_proof(&root, &leaf, 0, u32::MAX, &proof));
+	assert!(!merkle::verify_proof(&root, &leaf, 0, u32::MAX, &proof[..31]));
+	assert!(!merkle::verify_proof(&root, &leaf, u32::MAX, u32::MAX, &proof));
+}
+
+/// 所有者授权操作员，操作员可以转移存证，转移后授权失效
+#[test]
+fn claim_operator_works() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![1, 2];
+		assert_ok!(ProofModule::create_claim(Origin::signed(1), claim.clone(), None));
+
+		// 没有授权时账户2不能转移存证
+		assert_noop!(
+			ProofModule::transfer_claim(Origin::signed(2), claim.clone(), 3),
+			Error::<Test>::NotClaimOwner
+		);
+
+		assert_ok!(ProofModule::approve_claim_operator(Origin::signed(1), claim.clone(), 2));
+		System::assert_last_event(Event::ProofModule(crate::Event::ClaimOperatorApproved(
+			1,
+			claim.clone(),
+			2,
+		)));
+		// 重复授权不会重复记录
+		assert_ok!(ProofModule::approve_claim_operator(Origin::signed(1), claim.clone(), 2));
+		assert_eq!(ClaimOperators::<Test>::get(&claim).into_inner(), vec![2]);
+
+		// 只有所有者可以授权，授权数量有上限
+		assert_noop!(
+			ProofModule::approve_claim_operator(Origin::signed(2), claim.clone(), 3),
+			Error::<Test>::NotClaimOwner
+		);
+		assert_ok!(ProofModule::approve_claim_operator(Origin::signed(1), claim.clone(), 3));
+		assert_noop!(
+			ProofModule::approve_claim_operator(Origin::signed(1), claim.clone(), 4),
+			Error::<Test>::TooManyOperators
+		);
+
+		// 操作员把存证转移给账户3，原所有者设置的授权随之失效
+		assert_ok!(ProofModule::transfer_claim(Origin::signed(2), claim.clone(), 3));
+		assert_eq!(Proofs::<Test>::get(&claim).map(|(owner, _)| owner), Some(3));
+		assert!(ClaimOperators::<Test>::get(&claim).is_empty());
+		assert_noop!(
+			ProofModule::revoke_claim(Origin::signed(2), claim.clone()),
+			Error::<Test>::NotClaimOwner
+		);
+	})
+}
+
+/// 取消存证的操作员授权
+#[test]
+fn cancel_claim_operator_works() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![1, 2];
+		assert_ok!(ProofModule::create_claim(Origin::signed(1), claim.clone(), None));
+		assert_ok!(ProofModule::approve_claim_operator(Origin::signed(1), claim.clone(), 2));
+
+		assert_noop!(
+			ProofModule::cancel_claim_operator(Origin::signed(1), claim.clone(), 3),
+			Error::<Test>::NotClaimOperator
+		);
+		assert_ok!(ProofModule::cancel_claim_operator(Origin::signed(1), claim.clone(), 2));
+		System::assert_last_event(Event::ProofModule(crate::Event::ClaimOperatorCancelled(
+			1,
+			claim.clone(),
+			2,
+		)));
+		assert!(!ClaimOperators::<Test>::contains_key(&claim));
+
+		assert_noop!(
+			ProofModule::revoke_claim(Origin::signed(2), claim.clone()),
+			Error::<Test>::NotClaimOwner
+		);
+	})
+}
+
+/// 账户级别的操作员可以注销所有者的全部存证
+#[test]
+fn operator_for_all_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(ProofModule::create_claim(Origin::signed(1), vec![1], None));
+		assert_ok!(ProofModule::create_claim(Origin::signed(1), vec![2], None));
+
+		assert_ok!(ProofModule::set_operator_for_all(Origin::signed(1), 2, true));
+		System::assert_last_event(Event::ProofModule(crate::Event::OperatorForAllSet(1, 2, true)));
+
+		assert_ok!(ProofModule::revoke_claim(Origin::signed(2), vec![1]));
+		assert!(!Proofs::<Test>::contains_key(vec![1]));
+
+		assert_ok!(ProofModule::set_operator_for_all(Origin::signed(1), 2, false));
+		assert_noop!(
+			ProofModule::revoke_claim(Origin::signed(2), vec![2]),
+			Error::<Test>::NotClaimOwner
+		);
+	})
+}
+
+/// 存证的溯源记录依次记录创建、转移和注销，注销后仍然可以查询
+#[test]
+fn claim_history_works() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![1, 2];
+		let record = |action, block_number| ProvenanceRecord { action, block_number };
+
+		assert_ok!(ProofModule::create_claim(Origin::signed(1), claim.clone(), None));
+		System::set_block_number(2);
+		assert_ok!(ProofModule::transfer_claim(Origin::signed(1), claim.clone(), 2));
+		// 转移给自己不会改变所有权，不产生记录
+		assert_ok!(ProofModule::transfer_claim(Origin::signed(2), claim.clone(), 2));
+
+		assert_eq!(
+			ProofModule::claim_history(&claim).into_inner(),
+			vec![
+				record(ProvenanceAction::Created(1), 1),
+				record(ProvenanceAction::Transferred(1, 2), 2),
+			]
+		);
+
+		System::set_block_number(3);
+		assert_ok!(ProofModule::revoke_claim(Origin::signed(2), claim.clone()));
+		assert_eq!(
+			ProofModule::claim_history(&claim).into_inner(),
+			vec![
+				record(ProvenanceAction::Created(1), 1),
+				record(ProvenanceAction::Transferred(1, 2), 2),
+				record(ProvenanceAction::Revoked { by: 2, at: 3 }, 3),
+			]
+		);
+	})
+}
+
+/// 过期的存证在溯源记录中追加过期记录
+#[test]
+fn claim_history_records_expiry() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![1, 2];
+		assert_ok!(ProofModule::create_claim(Origin::signed(1), claim.clone(), Some(2)));
+
+		System::set_block_number(3);
+		ProofModule::on_initialize(3);
+		assert!(!Proofs::<Test>::contains_key(&claim));
+
+		let actions: Vec<_> =
+			ProofModule::claim_history(&claim).into_iter().map(|r| r.action).collect();
+		assert_eq!(
+			actions,
+			vec![ProvenanceAction::Created(1), ProvenanceAction::Expired { at: 3 }]
+		);
+	})
+}
+
+/// 溯源记录数量达到上限后保留创建记录，丢弃之后最早的记录，最后一个位置留给注销记录
+#[test]
+fn claim_history_is_bounded() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![1, 2];
+		assert_ok!(ProofModule::create_claim(Origin::signed(1), claim.clone(), None));
+		assert_ok!(ProofModule::transfer_claim(Origin::signed(1), claim.clone(), 2));
+		assert_ok!(ProofModule::transfer_claim(Origin::signed(2), claim.clone(), 3));
+		assert_ok!(ProofModule::transfer_claim(Origin::signed(3), claim.clone(), 1));
+		// 来回转移不能挤掉创建记录
+		assert_ok!(ProofModule::transfer_claim(Origin::signed(1), claim.clone(), 2));
+
+		let actions: Vec<_> =
+			ProofModule::claim_history(&claim).into_iter().map(|r| r.action).collect();
+		assert_eq!(
+			actions,
+			vec![ProvenanceAction::Created(1), ProvenanceAction::Transferred(1, 2)]
+		);
+
+		// 记录已满时注销记录仍然能写入
+		assert_ok!(ProofModule::revoke_claim(Origin::signed(2), claim.clone()));
+		let actions: Vec<_> =
+			ProofModule::claim_history(&claim).into_iter().map(|r| r.action).collect();
+		assert_eq!(
+			actions,
+			vec![
+				ProvenanceAction::Created(1),
+				ProvenanceAction::Transferred(1, 2),
+				ProvenanceAction::Revoked { by: 2, at: 1 },
+			]
+		);
+	})
+}
+
+/// 兄弟平行链的来源
+fn sibling(para: u32) -> Origin {
+	cumulus_pallet_xcm::Origin::SiblingParachain(para.into()).into()
+}
+
+/// 兄弟平行链查询本链的存证，查询结果通过Transact发回给对方
+#[test]
+fn verify_claim_works() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![1, 2];
+		assert_ok!(ProofModule::create_claim(Origin::signed(1), claim.clone(), None));
+
+		assert_ok!(ProofModule::verify_claim(sibling(2000), 7, claim.clone()));
+		System::assert_last_event(Event::ProofModule(crate::Event::ClaimVerified(
+			2000.into(),
+			7,
+			claim.clone(),
+			Some((1, 1)),
+		)));
+
+		let call = Call::ProofModule(crate::Call::claim_verified {
+			query_id: 7,
+			claim: claim.clone(),
+			result: Some((1, 1)),
+		});
+		assert_eq!(
+			sent_xcm(),
+			vec![(
+				(1, Junction::Parachain(2000)).into(),
+				Xcm(vec![Transact {
+					origin_type: OriginKind::Native,
+					require_weight_at_most: XcmWeightAtMost::get(),
+					call: call.encode().into(),
+				}])
+			)]
+		);
+
+		// 不存在的存证返回None
+		assert_ok!(ProofModule::verify_claim(sibling(2000), 8, vec![9]));
+		System::assert_last_event(Event::ProofModule(crate::Event::ClaimVerified(
+			2000.into(),
+			8,
+			vec![9],
+			None,
+		)));
+
+		// 发送失败时记录事件
+		assert_ok!(ProofModule::verify_claim(sibling(9999), 9, claim.clone()));
+		assert!(matches!(
+			System::events().last().map(|r| r.event.clone()),
+			Some(Event::ProofModule(crate::Event::ErrorSendingVerification(_, _, 9)))
+		));
+
+		// 每次查询从对方的主权账户收取VerificationDeposit
+		assert_eq!(Balances::free_balance(3000), 90);
+		assert_eq!(Balances::free_balance(10999), 95);
+	})
+}
+
+/// 主权账户余额不足的兄弟平行链不能查询存证
+#[test]
+fn verify_claim_requires_sibling_fee() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![1, 2];
+		assert_ok!(ProofModule::create_claim(Origin::signed(1), claim.clone(), None));
+
+		assert_noop!(
+			ProofModule::verify_claim(sibling(2001), 7, claim.clone()),
+			Error::<Test>::SiblingCannotPay
+		);
+		assert!(sent_xcm().is_empty());
+	})
+}
+
+/// 只有兄弟平行链可以调用跨链查询的接口
+#[test]
+fn verify_claim_requires_sibling_origin() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			ProofModule::verify_claim(Origin::signed(1), 0, vec![1]),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_noop!(
+			ProofModule::claim_verified(Origin::signed(1), 0, vec![1], None),
+			sp_runtime::DispatchError::BadOrigin
+		);
+	})
+}
+
+/// 向兄弟平行链发起查询，并接收对方返回的结果
+#[test]
+fn request_claim_verification_works() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![1, 2];
+		assert_ok!(ProofModule::request_claim_verification(
+			Origin::signed(1),
+			2000.into(),
+			claim.clone()
+		));
+		System::assert_last_event(Event::ProofModule(crate::Event::VerificationRequested(
+			1,
+			2000.into(),
+			0,
+			claim.clone(),
+		)));
+		assert_eq!(sent_xcm().len(), 1);
+		assert_eq!(NextVerificationId::<Test>::get(), 1);
+		assert!(PendingVerifications::<Test>::contains_key(0));
+		// 发起查询需要质押
+		assert_eq!(Balances::reserved_balance(1), 5);
+
+		// 其他平行链返回的结果不被接受
+		assert_ok!(ProofModule::claim_verified(sibling(3000), 0, claim.clone(), None));
+		System::assert_last_event(Event::ProofModule(crate::Event::UnknownVerification(
+			3000.into(),
+			0,
+		)));
+
+		assert_ok!(ProofModule::claim_verified(sibling(2000), 0, claim.clone(), Some((5, 3))));
+		System::assert_last_event(Event::ProofModule(crate::Event::VerificationReceived(
+			2000.into(),
+			0,
+			claim.clone(),
+			Some((5, 3)),
+		)));
+		assert!(!PendingVerifications::<Test>::contains_key(0));
+		// 收到结果后退回质押
+		assert_eq!(Balances::reserved_balance(1), 0);
+
+		// 发送失败时不记录查询，也不质押
+		assert_noop!(
+			ProofModule::request_claim_verification(Origin::signed(1), 9999.into(), claim),
+			Error::<Test>::XcmSendError
+		);
+	})
+}
+
+/// 发起查询需要足够的余额用于质押，查询的存证不能超长
+#[test]
+fn request_claim_verification_fails() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			ProofModule::request_claim_verification(Origin::signed(4), 2000.into(), vec![1]),
+			Error::<Test>::NoSufficientBalance
+		);
+		assert_noop!(
+			ProofModule::request_claim_verification(
+				Origin::signed(1),
+				2000.into(),
+				vec![1; MaxAddend::get() + 1]
+			),
+			Error::<Test>::ClaimOutLength
+		);
+	})
+}
+
+/// 超时没有收到结果的查询可以被清除，质押退回给发起查询的账户
+#[test]
+fn cancel_verification_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(ProofModule::request_claim_verification(
+			Origin::signed(1),
+			2000.into(),
+			vec![1]
+		));
+		assert_eq!(Balances::reserved_balance(1), 5);
+
+		assert_noop!(
+			ProofModule::cancel_verification(Origin::signed(2), 1),
+			Error::<Test>::VerificationNotFound
+		);
+		// 查询在区块11超时
+		System::set_block_number(11);
+		assert_noop!(
+			ProofModule::cancel_verification(Origin::signed(2), 0),
+			Error::<Test>::VerificationNotExpired
+		);
+
+		System::set_block_number(12);
+		assert_ok!(ProofModule::cancel_verification(Origin::signed(2), 0));
+		System::assert_last_event(Event::ProofModule(crate::Event::VerificationTimedOut(
+			1,
+			2000.into(),
+			0,
+		)));
+		assert!(!PendingVerifications::<Test>::contains_key(0));
+		assert_eq!(Balances::reserved_balance(1), 0);
+
+		// 清除后收到的结果被当作未知的查询
+		assert_ok!(ProofModule::claim_verified(sibling(2000), 0, vec![1], None));
+		System::assert_last_event(Event::ProofModule(crate::Event::UnknownVerification(
+			2000.into(),
+			0,
+		)));
+	})
+}
+
+/// 账户1签名的链下声明
+fn attestation(
+	claim: &[u8],
+	nonce: u64,
+	deadline: u64,
+) -> (ClaimAttestation<u64, u64>, TestSignature) {
+	signed_attestation(1, claim, nonce, deadline)
+}
+
+/// owner签名的链下声明
+fn signed_attestation(
+	owner: u64,
+	claim: &[u8],
+	nonce: u64,
+	deadline: u64,
+) -> (ClaimAttestation<u64, u64>, TestSignature) {
+	let attestation = ClaimAttestation { owner, claim: claim.to_vec(), nonce, deadline };
+	let signature = TestSignature(owner, ProofModule::attestation_payload(&attestation));
+	(attestation, signature)
+}
+
+fn validate(
+	attestation: ClaimAttestation<u64, u64>,
+	signature: TestSignature,
+) -> TransactionValidity {
+	let call = crate::Call::create_claim_with_signature { attestation, signature };
+	<ProofModule as ValidateUnsigned>::validate_unsigned(TransactionSource::External, &call)
+}
+
+/// 中继者提交链下签名的声明创建存证
+#[test]
+fn create_claim_with_signature_works() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![1, 2];
+		let (att, sig) = attestation(&claim, 0, 10);
+
+		let valid = validate(att.clone(), sig.clone()).unwrap();
+		assert_eq!(valid.priority, UnsignedPriority::get());
+		assert_eq!(valid.longevity, 9);
+
+		assert_ok!(ProofModule::create_claim_with_signature(
+			Origin::none(),
+			att.clone(),
+			sig.clone()
+		));
+		assert_eq!(Proofs::<Test>::get(&claim), Some((1, 1)));
+		assert_eq!(AttestationNonces::<Test>::get(1), 1);
+		// 质押从所有者的账户中扣除
+		assert_eq!(Balances::reserved_balance(1), 4);
+		System::assert_last_event(Event::ProofModule(crate::Event::ClaimCreated(1, claim.clone())));
+
+		// 注销后重放同一个声明会被拒绝
+		assert_ok!(ProofModule::revoke_claim(Origin::signed(1), claim.clone()));
+		assert_eq!(validate(att.clone(), sig.clone()), InvalidTransaction::Stale.into());
+		assert_noop!(
+			ProofModule::create_claim_with_signature(Origin::none(), att, sig),
+			Error::<Test>::InvalidAttestationNonce
+		);
+	})
+}
+
+/// 无效的链下声明在交易池中被拒绝，也不能被执行
+#[test]
+fn create_claim_with_signature_fails() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(5);
+
+		// 签名者与所有者不一致
+		let (att, _) = attestation(&[1], 0, 10);
+		let forged = TestSignature(2, ProofModule::attestation_payload(&att));
+		assert_eq!(validate(att.clone(), forged.clone()), InvalidTransaction::BadProof.into());
+		assert_noop!(
+			ProofModule::create_claim_with_signature(Origin::none(), att, forged),
+			Error::<Test>::BadSignature
+		);
+
+		// 超过截止区块
+		let (att, sig) = attestation(&[1], 0, 4);
+		assert_eq!(validate(att.clone(), sig.clone()), InvalidTransaction::Stale.into());
+		assert_noop!(
+			ProofModule::create_claim_with_signature(Origin::none(), att, sig),
+			Error::<Test>::AttestationExpired
+		);
+
+		// 序号超前
+		let (att, sig) = attestation(&[1], 1, 10);
+		assert_eq!(validate(att.clone(), sig.clone()), InvalidTransaction::Future.into());
+		assert_noop!(
+			ProofModule::create_claim_with_signature(Origin::none(), att, sig),
+			Error::<Test>::InvalidAttestationNonce
+		);
+
+		// 存证超长
+		let (att, sig) = attestation(&vec![1; MaxAddend::get() + 1], 0, 10);
+		assert_eq!(validate(att.clone(), sig.clone()), InvalidTransaction::Custom(1).into());
+		assert_noop!(
+			ProofModule::create_claim_with_signature(Origin::none(), att, sig),
+			Error::<Test>::ClaimOutLength
+		);
+
+		// 存证已经存在
+		assert_ok!(ProofModule::create_claim(Origin::signed(2), vec![1], None));
+		let (att, sig) = attestation(&[1], 0, 10);
+		assert_eq!(validate(att.clone(), sig.clone()), InvalidTransaction::Custom(2).into());
+		assert_noop!(
+			ProofModule::create_claim_with_signature(Origin::none(), att, sig),
+			Error::<Test>::ProofAlreadyExist
+		);
+
+		// 只接受不签名交易
+		let (att, sig) = attestation(&[2], 0, 10);
+		assert_noop!(
+			ProofModule::create_claim_with_signature(Origin::signed(1), att, sig),
+			sp_runtime::DispatchError::BadOrigin
+		);
+	})
+}
+
+/// 签名内容包含前缀和创世区块hash，只对声明本身签名或者为其他链签名的声明无效
+#[test]
+fn attestation_signature_is_bound_to_chain() {
+	new_test_ext().execute_with(|| {
+		let (att, _) = attestation(&[1], 0, 10);
+
+		let bare = TestSignature(1, att.encode());
+		assert_eq!(validate(att.clone(), bare.clone()), InvalidTransaction::BadProof.into());
+		assert_noop!(
+			ProofModule::create_claim_with_signature(Origin::none(), att.clone(), bare),
+			Error::<Test>::BadSignature
+		);
+
+		let other_chain =
+			TestSignature(1, (ATTESTATION_CONTEXT, H256::repeat_byte(1), &att).encode());
+		assert_eq!(validate(att.clone(), other_chain), InvalidTransaction::BadProof.into());
+	})
+}
+
+/// 所有者无法支付质押或者存证索引已满时，声明不能进入交易池
+#[test]
+fn attestation_rejected_when_owner_cannot_create_claim() {
+	new_test_ext().execute_with(|| {
+		// 账户4没有余额
+		let (att, sig) = signed_attestation(4, &[1], 0, 10);
+		assert_eq!(validate(att.clone(), sig.clone()), InvalidTransaction::Payment.into());
+		assert_noop!(
+			ProofModule::create_claim_with_signature(Origin::none(), att, sig),
+			Error::<Test>::NoSufficientBalance
+		);
+
+		// 账户1的存证索引已满
+		for claim in [vec![1], vec![2], vec![3]] {
+			assert_ok!(ProofModule::create_claim(Origin::signed(1), claim, None));
+		}
+		let (att, sig) = attestation(&[4], 0, 10);
+		assert_eq!(validate(att.clone(), sig.clone()), InvalidTransaction::Custom(3).into());
+		assert_noop!(
+			ProofModule::create_claim_with_signature(Origin::none(), att, sig),
+			Error::<Test>::TooManyClaims
+		);
+	})
+}
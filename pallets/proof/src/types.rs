@@ -0,0 +1,100 @@
+use codec::{Decode, Encode};
+
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+use cumulus_primitives_core::ParaId;
+use scale_info::TypeInfo;
+use sp_runtime::RuntimeDebug;
+use sp_std::vec::Vec;
+
+/// 生成存证hash值时使用的hash算法
+#[derive(Clone, Copy, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub enum HashAlgorithm {
+	Blake2_256,
+	Sha2_256,
+	Keccak256,
+}
+
+/// 存证的元数据
+#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct ClaimMetadata {
+	/// 生成存证hash值时使用的hash算法
+	pub hash_algorithm: HashAlgorithm,
+	/// 被存证文件的MIME类型，例如`application/pdf`
+	pub content_type: Vec<u8>,
+	/// 被存证文件的地址(可选)
+	pub uri: Option<Vec<u8>>,
+	/// 备注
+	pub memo: Vec<u8>,
+}
+
+impl ClaimMetadata {
+	/// 检查每个字段的长度是否都不超过max_len
+	pub fn within_length(&self, max_len: usize) -> bool {
+		self.content_type.len() <= max_len &&
+			self.uri.as_ref().map_or(true, |uri| uri.len() <= max_len) &&
+			self.memo.len() <= max_len
+	}
+}
+
+/// 存证的一次所有权变化
+#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub enum ProvenanceAction<AccountId, BlockNumber> {
+	/// 存证被创建，参数为创建者
+	Created(AccountId),
+	/// 存证被转移(包括在交易市场中被购买)，参数为(原所有者, 新所有者)
+	Transferred(AccountId, AccountId),
+	/// 存证被注销，`by`为发起注销的账户(可能是所有者授权的操作员)，`at`为注销的区块
+	Revoked { by: AccountId, at: BlockNumber },
+	/// 存证到期被移除，`at`为存证的过期区块
+	Expired { at: BlockNumber },
+}
+
+/// 存证溯源记录中的一条记录
+#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct ProvenanceRecord<AccountId, BlockNumber> {
+	/// 发生了什么
+	pub action: ProvenanceAction<AccountId, BlockNumber>,
+	/// 发生时的区块高度
+	pub block_number: BlockNumber,
+}
+
+/// 所有者在链下签名的存证声明，由中继者通过不签名交易提交上链
+///
+/// 所有者对`(ATTESTATION_CONTEXT, 创世区块hash, 声明)`SCALE编码后的字节进行签名，
+/// 见`Pallet::attestation_payload`
+#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct ClaimAttestation<AccountId, BlockNumber> {
+	/// 存证的所有者，也是签名者
+	pub owner: AccountId,
+	/// 存证的hash值
+	pub claim: Vec<u8>,
+	/// 所有者的声明序号，必须等于链上记录的下一个序号，防止声明被重放
+	pub nonce: u64,
+	/// 声明的截止区块，超过该区块后声明失效
+	pub deadline: BlockNumber,
+}
+
+/// 已经发出、还没有收到结果的跨链查询
+#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct PendingVerification<AccountId, Balance, BlockNumber> {
+	/// 发起查询的账户
+	pub requester: AccountId,
+	/// 被查询的兄弟平行链
+	pub para: ParaId,
+	/// 被查询的存证
+	pub claim: Vec<u8>,
+	/// 发起查询时的质押
+	pub deposit: Balance,
+	/// 超过该区块后查询可以被清除
+	pub expires_at: BlockNumber,
+}
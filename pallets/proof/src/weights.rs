@@ -0,0 +1,170 @@
+//! 存证模块(pallet-proof)各个交易的weight
+//!
+//! 这里的数值是人工估算的，不是benchmark的结果：数据库读写次数按照代码中访问的存储统计，
+//! 基础执行时间取了偏保守的整数。部署到正式链之前需要在参考硬件上运行`benchmarking.rs`中的
+//! benchmark，用生成的结果替换这个文件。
+
+use frame_support::{
+	traits::Get,
+	weights::{constants::RocksDbWeight, RuntimeDbWeight, Weight},
+};
+use sp_std::marker::PhantomData;
+
+/// 存证模块各个交易需要的weight
+pub trait WeightInfo {
+	fn create_claim(l: u32) -> Weight;
+	fn create_merkle_claim() -> Weight;
+	fn revoke_claim() -> Weight;
+	fn transfer_claim() -> Weight;
+	fn sell_claim() -> Weight;
+	fn cancel_claim_sale() -> Weight;
+	fn buy_claim() -> Weight;
+	fn approve_claim_operator() -> Weight;
+	fn cancel_claim_operator() -> Weight;
+	fn set_operator_for_all() -> Weight;
+	fn set_claim_metadata() -> Weight;
+	fn create_claim_with_signature(l: u32) -> Weight;
+	fn request_claim_verification() -> Weight;
+	fn verify_claim() -> Weight;
+	fn claim_verified() -> Weight;
+	fn cancel_verification() -> Weight;
+	fn on_initialize(n: u32) -> Weight;
+}
+
+/// 每微秒对应的weight
+const MICROS: Weight = 1_000_000;
+
+/// 估算的weight：基础执行时间(微秒)加上数据库的读写
+fn estimate<W: Get<RuntimeDbWeight>>(micros: Weight, reads: Weight, writes: Weight) -> Weight {
+	(micros * MICROS).saturating_add(W::get().reads_writes(reads, writes))
+}
+
+/// 各个交易的估算weight，`W`为数据库读写的weight
+fn create_claim<W: Get<RuntimeDbWeight>>(l: u32) -> Weight {
+	// 存证hash每个字节需要约0.015微秒
+	estimate::<W>(45, 5, 7).saturating_add((15_000 as Weight).saturating_mul(l as Weight))
+}
+
+fn create_claim_with_signature<W: Get<RuntimeDbWeight>>(l: u32) -> Weight {
+	// 包括校验签名的时间
+	estimate::<W>(100, 6, 6).saturating_add((15_000 as Weight).saturating_mul(l as Weight))
+}
+
+fn on_initialize<W: Get<RuntimeDbWeight>>(n: u32) -> Weight {
+	// 读写一次过期队列，每个过期的存证按注销存证计算
+	let n = n as Weight;
+	estimate::<W>(5, 1, 1)
+		.saturating_add((50 * MICROS).saturating_mul(n))
+		.saturating_add(W::get().reads_writes(6u64.saturating_mul(n), 11u64.saturating_mul(n)))
+}
+
+/// 使用runtime中配置的数据库读写weight
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	fn create_claim(l: u32) -> Weight {
+		create_claim::<T::DbWeight>(l)
+	}
+	fn create_merkle_claim() -> Weight {
+		estimate::<T::DbWeight>(50, 5, 8)
+	}
+	fn revoke_claim() -> Weight {
+		estimate::<T::DbWeight>(55, 8, 11)
+	}
+	fn transfer_claim() -> Weight {
+		estimate::<T::DbWeight>(60, 9, 8)
+	}
+	fn sell_claim() -> Weight {
+		estimate::<T::DbWeight>(25, 1, 1)
+	}
+	fn cancel_claim_sale() -> Weight {
+		estimate::<T::DbWeight>(25, 2, 1)
+	}
+	fn buy_claim() -> Weight {
+		estimate::<T::DbWeight>(80, 8, 8)
+	}
+	fn approve_claim_operator() -> Weight {
+		estimate::<T::DbWeight>(25, 2, 1)
+	}
+	fn cancel_claim_operator() -> Weight {
+		estimate::<T::DbWeight>(25, 2, 1)
+	}
+	fn set_operator_for_all() -> Weight {
+		estimate::<T::DbWeight>(20, 0, 1)
+	}
+	fn set_claim_metadata() -> Weight {
+		estimate::<T::DbWeight>(40, 3, 3)
+	}
+	fn create_claim_with_signature(l: u32) -> Weight {
+		create_claim_with_signature::<T::DbWeight>(l)
+	}
+	fn request_claim_verification() -> Weight {
+		estimate::<T::DbWeight>(65, 6, 5)
+	}
+	fn verify_claim() -> Weight {
+		estimate::<T::DbWeight>(50, 6, 3)
+	}
+	fn claim_verified() -> Weight {
+		estimate::<T::DbWeight>(30, 2, 2)
+	}
+	fn cancel_verification() -> Weight {
+		estimate::<T::DbWeight>(30, 2, 2)
+	}
+	fn on_initialize(n: u32) -> Weight {
+		on_initialize::<T::DbWeight>(n)
+	}
+}
+
+// 测试中使用，数据库读写按RocksDb计算
+impl WeightInfo for () {
+	fn create_claim(l: u32) -> Weight {
+		create_claim::<RocksDbWeight>(l)
+	}
+	fn create_merkle_claim() -> Weight {
+		estimate::<RocksDbWeight>(50, 5, 8)
+	}
+	fn revoke_claim() -> Weight {
+		estimate::<RocksDbWeight>(55, 8, 11)
+	}
+	fn transfer_claim() -> Weight {
+		estimate::<RocksDbWeight>(60, 9, 8)
+	}
+	fn sell_claim() -> Weight {
+		estimate::<RocksDbWeight>(25, 1, 1)
+	}
+	fn cancel_claim_sale() -> Weight {
+		estimate::<RocksDbWeight>(25, 2, 1)
+	}
+	fn buy_claim() -> Weight {
+		estimate::<RocksDbWeight>(80, 8, 8)
+	}
+	fn approve_claim_operator() -> Weight {
+		estimate::<RocksDbWeight>(25, 2, 1)
+	}
+	fn cancel_claim_operator() -> Weight {
+		estimate::<RocksDbWeight>(25, 2, 1)
+	}
+	fn set_operator_for_all() -> Weight {
+		estimate::<RocksDbWeight>(20, 0, 1)
+	}
+	fn set_claim_metadata() -> Weight {
+		estimate::<RocksDbWeight>(40, 3, 3)
+	}
+	fn create_claim_with_signature(l: u32) -> Weight {
+		create_claim_with_signature::<RocksDbWeight>(l)
+	}
+	fn request_claim_verification() -> Weight {
+		estimate::<RocksDbWeight>(65, 6, 5)
+	}
+	fn verify_claim() -> Weight {
+		estimate::<RocksDbWeight>(50, 6, 3)
+	}
+	fn claim_verified() -> Weight {
+		estimate::<RocksDbWeight>(30, 2, 2)
+	}
+	fn cancel_verification() -> Weight {
+		estimate::<RocksDbWeight>(30, 2, 2)
+	}
+	fn on_initialize(n: u32) -> Weight {
+		on_initialize::<RocksDbWeight>(n)
+	}
+}
@@ -11,8 +11,11 @@ use sp_api::impl_runtime_apis;
 use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
 use sp_runtime::{
 	create_runtime_str, generic, impl_opaque_keys,
-	traits::{AccountIdLookup, BlakeTwo256, Block as BlockT, IdentifyAccount, Verify},
-	transaction_validity::{TransactionSource, TransactionValidity},
+	traits::{
+		AccountIdConversion, AccountIdLookup, BlakeTwo256, Block as BlockT, Convert, Hash as HashT,
+		IdentifyAccount, Verify,
+	},
+	transaction_validity::{TransactionPriority, TransactionSource, TransactionValidity},
 	ApplyExtrinsicResult, MultiSignature,
 };
 
@@ -23,7 +26,7 @@ use sp_version::RuntimeVersion;
 
 use frame_support::{
 	construct_runtime, match_type, parameter_types,
-	traits::{Everything, Nothing},
+	traits::{Everything, Nothing, Randomness},
 	weights::{
 		constants::{BlockExecutionWeight, ExtrinsicBaseWeight, WEIGHT_PER_SECOND},
 		DispatchClass, IdentityFee, Weight, WeightToFeeCoefficient, WeightToFeeCoefficients,
@@ -63,6 +66,8 @@ pub use cumulus_ping;
 pub use pallet_kitties;
 
 pub use pallet_xclient;
+
+pub use pallet_proof;
 /// Alias to 512-bit hash when used in the context of a transaction signature on the chain.
 pub type Signature = MultiSignature;
 
@@ -179,10 +184,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	spec_name: create_runtime_str!("template-parachain"),
 	impl_name: create_runtime_str!("template-parachain"),
 	authoring_version: 1,
-	spec_version: 1,
+	spec_version: 2,
 	impl_version: 0,
 	apis: RUNTIME_API_VERSIONS,
-	transaction_version: 0,
+	transaction_version: 1,
 	state_version: 0,
 };
 
@@ -616,18 +621,141 @@ impl cumulus_ping::Config for Runtime {
 
 impl pallet_randomness_collective_flip::Config for Runtime {}
 
+/// Randomness sourced from the relay chain.
+///
+/// Mixes the subject with the storage root of the relay parent, which is set by the
+/// `set_validation_data` inherent and cannot be chosen by the collator. Outside of a block
+/// with validation data (e.g. in `on_initialize`) there is no randomness and it returns `None`;
+/// callers defer whatever needed it.
+pub struct RelayChainRandomness;
+
+impl Randomness<Option<Hash>, BlockNumber> for RelayChainRandomness {
+	fn random(subject: &[u8]) -> (Option<Hash>, BlockNumber) {
+		let random = ParachainSystem::validation_data()
+			.map(|data| BlakeTwo256::hash_of(&(subject, data.relay_parent_storage_root)));
+		(random, System::block_number())
+	}
+}
+
 parameter_types! {
     pub const ReservationFee: Balance = 10;
+	pub const KittyListingDeposit: Balance = 10 * MILLIUNIT;
+	pub const KittyMarketplaceFee: Permill = Permill::from_percent(2);
+	pub const KittyTreasuryPalletId: PalletId = PalletId(*b"py/kttre");
+	pub KittyTreasuryAccount: AccountId = KittyTreasuryPalletId::get().into_account();
+	pub const KittyMaxAuctionsPerBlock: u32 = 50;
+	pub const KittyBreedingCooldown: BlockNumber = 10 * MINUTES;
+	pub const KittyMintDelay: BlockNumber = 2;
+	pub const KittyMaxPendingMintsPerBlock: u32 = 50;
+	pub const KittyXcmWeightAtMost: u64 = 1_000_000_000;
+	pub KittyDestination: ParaId = ParaId::from(4000);
+	pub const KittyMaxMetadataUriLen: u32 = 256;
 }
 
 impl pallet_kitties::Config for Runtime {
 	type Event = Event;
-	type Randomness = RandomnessCollectiveFlip;
+	type Randomness = RelayChainRandomness;
 	type Currency = Balances;
 	type KittyIndex = u64;
 	type ReservationFee = ReservationFee;
+	type ListingDeposit = KittyListingDeposit;
+	type MarketplaceFee = KittyMarketplaceFee;
+	type TreasuryAccount = KittyTreasuryAccount;
+	type WeightInfo = pallet_kitties::weights::SubstrateWeight<Runtime>;
+	type MaxAuctionsPerBlock = KittyMaxAuctionsPerBlock;
+	type BreedingCooldown = KittyBreedingCooldown;
+	type MintDelay = KittyMintDelay;
+	type MaxPendingMintsPerBlock = KittyMaxPendingMintsPerBlock;
+	type Origin = Origin;
+	type Call = Call;
+	type XcmSender = XcmRouter;
+	type XcmWeightAtMost = KittyXcmWeightAtMost;
+	type KittyDestination = KittyDestination;
+	type MaxMetadataUriLen = KittyMaxMetadataUriLen;
 }
 
+parameter_types! {
+	// 存证内容(hash值)的最大长度，64字节足以容纳常见的512位hash
+	pub const MaxAddend: usize = 64;
+	// 每个账户最多拥有的存证数量
+	pub const MaxClaimsPerOwner: u32 = 256;
+	// 创建存证的质押：基础金额 + 每字节金额 * 存证长度
+	pub const ClaimDepositBase: Balance = 10 * MILLIUNIT;
+	pub const ClaimDepositPerByte: Balance = 100 * MICROUNIT;
+	// 同一个区块最多过期的存证数量
+	pub const MaxExpiringPerBlock: u32 = 64;
+	// 存证元数据每个字段的最大长度
+	pub const MaxMetadataLen: usize = 256;
+	// 每个存证最多授权的操作员数量
+	pub const MaxClaimOperators: u32 = 8;
+	// 每个存证最多保存的溯源记录数量
+	pub const MaxHistoryLen: u32 = 100;
+	// 跨链查询存证以及返回查询结果时，Transact最多可以使用的weight
+	pub const ProofXcmWeightAtMost: u64 = 1_000_000_000;
+	// 跨链查询存证的质押，收到结果或超时后退回；兄弟平行链查询本链存证时也收取这个金额
+	pub const ProofVerificationDeposit: Balance = 10 * MILLIUNIT;
+	// 跨链查询的超时时间
+	pub const ProofVerificationTimeout: BlockNumber = 1 * HOURS;
+	// 中继者提交链下声明的不签名交易在交易池中的优先级
+	pub const ProofUnsignedPriority: TransactionPriority = TransactionPriority::max_value() / 4;
+}
+
+/// 兄弟平行链在本链的主权账户，与`SiblingParachainConvertsVia<Sibling, AccountId>`一致
+pub struct SiblingSovereignAccount;
+impl Convert<ParaId, AccountId> for SiblingSovereignAccount {
+	fn convert(para: ParaId) -> AccountId {
+		Sibling::from(para).into_account()
+	}
+}
+
+impl pallet_proof::Config for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	type MaxAddend = MaxAddend;
+	type MaxClaimsPerOwner = MaxClaimsPerOwner;
+	type ClaimDepositBase = ClaimDepositBase;
+	type ClaimDepositPerByte = ClaimDepositPerByte;
+	type MaxExpiringPerBlock = MaxExpiringPerBlock;
+	type MaxMetadataLen = MaxMetadataLen;
+	type MaxClaimOperators = MaxClaimOperators;
+	type MaxHistoryLen = MaxHistoryLen;
+	type Origin = Origin;
+	type Call = Call;
+	type XcmSender = XcmRouter;
+	type XcmWeightAtMost = ProofXcmWeightAtMost;
+	type VerificationDeposit = ProofVerificationDeposit;
+	type VerificationTimeout = ProofVerificationTimeout;
+	type SiblingAccount = SiblingSovereignAccount;
+	type WeightInfo = pallet_proof::weights::SubstrateWeight<Runtime>;
+	type Signature = Signature;
+	type Signer = <Signature as Verify>::Signer;
+	type UnsignedPriority = ProofUnsignedPriority;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = ProofBenchmarkHelper;
+}
+
+/// Generates sr25519 keys in the benchmark keystore to sign claim attestations.
+#[cfg(feature = "runtime-benchmarks")]
+pub struct ProofBenchmarkHelper;
+
+#[cfg(feature = "runtime-benchmarks")]
+impl pallet_proof::benchmarking::BenchmarkHelper<AccountId, Signature> for ProofBenchmarkHelper {
+	fn signer() -> AccountId {
+		let public = sp_io::crypto::sr25519_generate(PROOF_BENCHMARK_KEY, None);
+		sp_runtime::MultiSigner::from(public).into_account()
+	}
+
+	fn sign(signer: &AccountId, payload: &[u8]) -> Signature {
+		let public = sp_core::sr25519::Public::from_raw(signer.clone().into());
+		sp_io::crypto::sr25519_sign(PROOF_BENCHMARK_KEY, &public, payload)
+			.expect("signer was generated in the benchmark keystore; qed")
+			.into()
+	}
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+const PROOF_BENCHMARK_KEY: KeyTypeId = KeyTypeId(*b"prfb");
+
 parameter_types! {
 	pub const XregisterWeightAtMost: u32 = 1_000_000;
 }
@@ -677,6 +805,7 @@ construct_runtime!(
 		PingPong: cumulus_ping::{Pallet, Call, Storage, Event<T>},
 		KittiesModule: pallet_kitties::{Pallet, Call, Storage, Event<T>},
 		Xclient: pallet_xclient::{Pallet, Call, Storage, Event<T>},
+		ProofModule: pallet_proof::{Pallet, Call, Storage, Event<T>, Config<T>, ValidateUnsigned},
 	}
 );
 
@@ -692,6 +821,7 @@ mod benches {
 		[pallet_session, SessionBench::<Runtime>]
 		[pallet_timestamp, Timestamp]
 		[pallet_collator_selection, CollatorSelection]
+		[pallet_proof, ProofModule]
 	);
 }
 
@@ -796,6 +926,70 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_proof_rpc_runtime_api::ProofApi<Block, AccountId, BlockNumber> for Runtime {
+		fn claim_owner(claim: Vec<u8>) -> Option<(AccountId, BlockNumber)> {
+			ProofModule::proofs(claim)
+		}
+
+		fn claims_by_owner(account: AccountId) -> Vec<Vec<u8>> {
+			ProofModule::claims_by_owner(&account)
+		}
+
+		fn claim_exists(claim: Vec<u8>) -> bool {
+			ProofModule::claim_exists(&claim)
+		}
+
+		fn claim_metadata(claim: Vec<u8>) -> Option<pallet_proof::ClaimMetadata> {
+			ProofModule::claim_metadata(claim)
+		}
+
+		fn claim_history(
+			claim: Vec<u8>,
+		) -> Vec<pallet_proof::ProvenanceRecord<AccountId, BlockNumber>> {
+			ProofModule::claim_history(claim).into_inner()
+		}
+
+		fn verify_merkle_inclusion(
+			root: [u8; 32],
+			leaf: [u8; 32],
+			index: u32,
+			proof: Vec<[u8; 32]>,
+		) -> bool {
+			ProofModule::verify_merkle_inclusion(root, leaf, index, proof)
+		}
+	}
+
+	impl pallet_kitties_rpc_runtime_api::KittiesApi<Block, AccountId, Balance, BlockNumber> for Runtime {
+		fn kitty_market() -> pallet_kitties::GetKittyMarketResult<AccountId, Balance> {
+			KittiesModule::query_kittiy_market_info()
+		}
+
+		fn kitty_market_page(
+			query: pallet_kitties::MarketQuery<AccountId, Balance>,
+		) -> pallet_kitties::MarketPage<AccountId, Balance> {
+			KittiesModule::query_kitty_market(query)
+		}
+
+		fn kitties_of(
+			owner: AccountId,
+			cursor: Option<pallet_kitties::KittyIndex>,
+			limit: u32,
+		) -> pallet_kitties::OwnedKittiesPage<Balance> {
+			KittiesModule::query_owned_kitties(&owner, cursor, limit)
+		}
+
+		fn family_tree(
+			kitty_index: pallet_kitties::KittyIndex,
+			depth: u32,
+		) -> Vec<pallet_kitties::FamilyTreeNode<BlockNumber>> {
+			KittiesModule::query_family_tree(kitty_index, depth)
+		}
+
+		fn kitty_traits(kitty_index: pallet_kitties::KittyIndex) -> Option<pallet_kitties::KittyTraits> {
+			KittiesModule::query_kitty_traits(kitty_index)
+		}
+	}
+
 	impl cumulus_primitives_core::CollectCollationInfo<Block> for Runtime {
 		fn collect_collation_info(header: &<Block as BlockT>::Header) -> cumulus_primitives_core::CollationInfo {
 			ParachainSystem::collect_collation_info(header)
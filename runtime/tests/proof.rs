@@ -0,0 +1,59 @@
+//! Integration tests for `pallet_proof` wired into the parachain runtime.
+
+use frame_support::{assert_noop, assert_ok, traits::Get};
+use sp_runtime::traits::Dispatchable;
+use Proof_runtime::{AccountId, Balances, Call, Event, Origin, ProofModule, Runtime, System, UNIT};
+
+const ALICE: AccountId = AccountId::new([1u8; 32]);
+const BOB: AccountId = AccountId::new([2u8; 32]);
+
+fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+	pallet_balances::GenesisConfig::<Runtime> { balances: vec![(ALICE, 10 * UNIT), (BOB, 10 * UNIT)] }
+		.assimilate_storage(&mut t)
+		.unwrap();
+	pallet_proof::GenesisConfig::<Runtime> { claims: vec![(BOB, b"genesis-claim".to_vec())] }
+		.assimilate_storage(&mut t)
+		.unwrap();
+
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+#[test]
+fn create_claim_through_runtime_call() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0xab; 32];
+		let call =
+			Call::ProofModule(pallet_proof::Call::create_claim { claim: claim.clone(), ttl: None });
+
+		assert_ok!(call.dispatch(Origin::signed(ALICE)));
+		assert_eq!(ProofModule::proofs(&claim), Some((ALICE, 1)));
+		assert_eq!(ProofModule::claims_by_owner(&ALICE), vec![claim.clone()]);
+		assert_eq!(Balances::reserved_balance(&ALICE), ProofModule::claim_deposit(&claim, None));
+		System::assert_last_event(Event::ProofModule(pallet_proof::Event::ClaimCreated(
+			ALICE, claim,
+		)));
+	})
+}
+
+#[test]
+fn create_claim_respects_runtime_max_addend() {
+	new_test_ext().execute_with(|| {
+		let claim = vec![0u8; <Runtime as pallet_proof::Config>::MaxAddend::get() + 1];
+
+		assert_noop!(
+			ProofModule::create_claim(Origin::signed(ALICE), claim, None),
+			pallet_proof::Error::<Runtime>::ClaimOutLength
+		);
+	})
+}
+
+#[test]
+fn genesis_claims_are_stored() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(ProofModule::proofs(b"genesis-claim".to_vec()), Some((BOB, 0)));
+		assert_eq!(ProofModule::claims_by_owner(&BOB), vec![b"genesis-claim".to_vec()]);
+	})
+}